@@ -0,0 +1,358 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+use petgraph::graphmap::UnGraphMap;
+use wg_2024::network::NodeId;
+
+/// A change to the topology whose consequences can be analyzed before it
+/// is applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    Crash(NodeId),
+    RemoveLink(NodeId, NodeId),
+}
+
+/// What a fault would break.
+#[derive(Default, Debug)]
+pub struct Impact {
+    /// client and server pairs that can communicate now, but not after the
+    /// fault.
+    pub unreachable: Vec<(NodeId, NodeId)>,
+    /// nodes that would be left without any link.
+    pub isolated: Vec<NodeId>,
+}
+
+impl Impact {
+    /// Returns true if the fault breaks nothing.
+    #[must_use]
+    pub fn is_safe(&self) -> bool {
+        self.unreachable.is_empty() && self.isolated.is_empty()
+    }
+}
+
+/// Returns what the fault would break in the topology. Clients and servers
+/// can communicate if there is a path between them through drones only.
+#[must_use]
+pub fn impact(
+    topology: &UnGraphMap<NodeId, ()>,
+    clients: &[NodeId],
+    servers: &[NodeId],
+    fault: Fault,
+) -> Impact {
+    let before_pairs = reachable_pairs(topology, clients, servers);
+    impact_with_pairs(topology, &before_pairs, clients, servers, fault)
+}
+
+/// returns what the fault would break, given the client and server pairs
+/// that can communicate before it.
+fn impact_with_pairs(
+    topology: &UnGraphMap<NodeId, ()>,
+    before_pairs: &BTreeSet<(NodeId, NodeId)>,
+    clients: &[NodeId],
+    servers: &[NodeId],
+    fault: Fault,
+) -> Impact {
+    let mut after = topology.clone();
+    match fault {
+        Fault::Crash(id) => {
+            after.remove_node(id);
+        }
+        Fault::RemoveLink(a, b) => {
+            after.remove_edge(a, b);
+        }
+    }
+
+    let after_pairs = reachable_pairs(&after, clients, servers);
+    let unreachable = before_pairs.difference(&after_pairs).copied().collect();
+
+    let isolated = after
+        .nodes()
+        .filter(|id| after.neighbors(*id).next().is_none())
+        .filter(|id| topology.neighbors(*id).next().is_some())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Impact {
+        unreachable,
+        isolated,
+    }
+}
+
+/// Returns the drones whose crash, and the links whose removal, would break
+/// something. Links have the smallest id first.
+#[must_use]
+pub fn unsafe_faults(
+    topology: &UnGraphMap<NodeId, ()>,
+    clients: &[NodeId],
+    servers: &[NodeId],
+    drones: &[NodeId],
+) -> (HashSet<NodeId>, HashSet<(NodeId, NodeId)>) {
+    let before_pairs = reachable_pairs(topology, clients, servers);
+    let is_unsafe =
+        |fault| !impact_with_pairs(topology, &before_pairs, clients, servers, fault).is_safe();
+    let drones = drones
+        .iter()
+        .copied()
+        .filter(|id| is_unsafe(Fault::Crash(*id)))
+        .collect();
+    let links = topology
+        .all_edges()
+        .map(|(a, b, ())| (a.min(b), a.max(b)))
+        .filter(|&(a, b)| is_unsafe(Fault::RemoveLink(a, b)))
+        .collect();
+    (drones, links)
+}
+
+/// Paths searched between a client and a server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PathMode {
+    /// only the paths with the fewest hops.
+    #[default]
+    Shortest,
+    /// every path that doesn't visit a node twice.
+    AllSimple,
+}
+
+/// Returns the paths from `from` to `to` passing through drones only,
+/// shortest first, and whether the search stopped at `limit` paths.
+#[must_use]
+pub fn paths(
+    topology: &UnGraphMap<NodeId, ()>,
+    drones: &[NodeId],
+    from: NodeId,
+    to: NodeId,
+    mode: PathMode,
+    limit: usize,
+) -> (Vec<Vec<NodeId>>, bool) {
+    if !topology.contains_node(from) || !topology.contains_node(to) {
+        return (vec![], false);
+    }
+    // hops from every node to `to`, through drones only, used to prune the
+    // search of the shortest paths
+    let mut distance = HashMap::from([(to, 0)]);
+    let mut queue = VecDeque::from([to]);
+    while let Some(id) = queue.pop_front() {
+        if id != to && !drones.contains(&id) {
+            continue;
+        }
+        for neighbor in topology.neighbors(id) {
+            if !distance.contains_key(&neighbor) {
+                distance.insert(neighbor, distance[&id] + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    let Some(&shortest) = distance.get(&from) else {
+        return (vec![], false);
+    };
+
+    let mut found = vec![];
+    let mut truncated = false;
+    let mut path = vec![from];
+    // depth-first search, one iterator of neighbors per node of the path
+    let mut stack = vec![sorted_neighbors(topology, from)];
+    while let Some(neighbors) = stack.last_mut() {
+        let Some(next) = neighbors.pop() else {
+            stack.pop();
+            path.pop();
+            continue;
+        };
+        if path.contains(&next) {
+            continue;
+        }
+        if next == to {
+            if found.len() == limit {
+                truncated = true;
+                break;
+            }
+            let mut complete = path.clone();
+            complete.push(to);
+            found.push(complete);
+            continue;
+        }
+        if !drones.contains(&next) {
+            continue;
+        }
+        if mode == PathMode::Shortest {
+            // the path can only stay shortest if `next` is one hop closer
+            let hops = path.len();
+            if !distance.get(&next).is_some_and(|d| hops + d == shortest) {
+                continue;
+            }
+        }
+        path.push(next);
+        stack.push(sorted_neighbors(topology, next));
+    }
+    found.sort_by_key(Vec::len);
+    (found, truncated)
+}
+
+/// returns the neighbors of a node, with the smallest id last.
+fn sorted_neighbors(topology: &UnGraphMap<NodeId, ()>, id: NodeId) -> Vec<NodeId> {
+    let mut neighbors: Vec<NodeId> = topology.neighbors(id).collect();
+    neighbors.sort_unstable_by(|a, b| b.cmp(a));
+    neighbors
+}
+
+/// Returns the probability that a fragment crosses every drone of the path
+/// without being dropped, given the PDR of each drone.
+#[must_use]
+pub fn delivery_probability(path: &[NodeId], pdr: impl Fn(NodeId) -> Option<f32>) -> f64 {
+    path.iter()
+        .filter_map(|id| pdr(*id))
+        .map(|pdr| 1.0 - f64::from(pdr))
+        .product()
+}
+
+/// returns the client and server pairs connected by a path through drones.
+fn reachable_pairs(
+    topology: &UnGraphMap<NodeId, ()>,
+    clients: &[NodeId],
+    servers: &[NodeId],
+) -> BTreeSet<(NodeId, NodeId)> {
+    let mut pairs = BTreeSet::new();
+    for &client in clients.iter().filter(|c| topology.contains_node(**c)) {
+        let mut visited = HashSet::from([client]);
+        let mut queue = VecDeque::from([client]);
+        while let Some(id) = queue.pop_front() {
+            for neighbor in topology.neighbors(id) {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                if servers.contains(&neighbor) {
+                    pairs.insert((client, neighbor));
+                } else if !clients.contains(&neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+    pairs
+}
+
+impl Display for Fault {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fault::Crash(id) => write!(f, "crash drone #{id}"),
+            Fault::RemoveLink(a, b) => write!(f, "remove the link between #{a} and #{b}"),
+        }
+    }
+}
+
+impl Display for PathMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathMode::Shortest => write!(f, "Shortest paths"),
+            PathMode::AllSimple => write!(f, "All simple paths"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT: NodeId = 10;
+    const SERVER: NodeId = 20;
+
+    fn topology(edges: &[(NodeId, NodeId)]) -> UnGraphMap<NodeId, ()> {
+        UnGraphMap::from_edges(edges)
+    }
+
+    #[test]
+    fn shortest_and_all_simple_paths() {
+        let t = topology(&[(10, 1), (1, 20), (10, 2), (2, 3), (3, 20)]);
+        let drones = [1, 2, 3];
+        assert_eq!(
+            paths(&t, &drones, CLIENT, SERVER, PathMode::Shortest, 10),
+            (vec![vec![10, 1, 20]], false)
+        );
+        assert_eq!(
+            paths(&t, &drones, CLIENT, SERVER, PathMode::AllSimple, 10),
+            (vec![vec![10, 1, 20], vec![10, 2, 3, 20]], false)
+        );
+    }
+
+    #[test]
+    fn paths_only_cross_drones() {
+        // node 11 is a client between two drones
+        let t = topology(&[(10, 1), (1, 11), (11, 2), (2, 20)]);
+        assert_eq!(
+            paths(&t, &[1, 2], CLIENT, SERVER, PathMode::AllSimple, 10),
+            (vec![], false)
+        );
+    }
+
+    #[test]
+    fn direct_client_server_link() {
+        let t = topology(&[(10, 20), (10, 1), (1, 20)]);
+        assert_eq!(
+            paths(&t, &[1], CLIENT, SERVER, PathMode::Shortest, 10),
+            (vec![vec![10, 20]], false)
+        );
+    }
+
+    #[test]
+    fn truncated_path_search() {
+        let t = topology(&[(10, 1), (1, 20), (10, 2), (2, 20), (10, 3), (3, 20)]);
+        let drones = [1, 2, 3];
+        assert_eq!(
+            paths(&t, &drones, CLIENT, SERVER, PathMode::Shortest, 2),
+            (vec![vec![10, 1, 20], vec![10, 2, 20]], true)
+        );
+        // reaching the limit exactly is not a truncation
+        let (found, truncated) = paths(&t, &drones, CLIENT, SERVER, PathMode::Shortest, 3);
+        assert_eq!(found.len(), 3);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn paths_of_missing_nodes() {
+        let t = topology(&[(10, 1), (1, 20)]);
+        assert_eq!(
+            paths(&t, &[1], CLIENT, 21, PathMode::Shortest, 10),
+            (vec![], false)
+        );
+    }
+
+    #[test]
+    fn crash_on_the_only_route() {
+        let t = topology(&[(10, 1), (1, 20), (20, 2)]);
+        let impact = impact(&t, &[CLIENT], &[SERVER], Fault::Crash(1));
+        assert_eq!(impact.unreachable, vec![(CLIENT, SERVER)]);
+        assert_eq!(impact.isolated, vec![CLIENT]);
+    }
+
+    #[test]
+    fn crash_of_an_isolated_drone() {
+        let mut t = topology(&[(10, 1), (1, 20)]);
+        t.add_node(5);
+        assert!(impact(&t, &[CLIENT], &[SERVER], Fault::Crash(5)).is_safe());
+    }
+
+    #[test]
+    fn removal_of_a_redundant_link() {
+        let t = topology(&[(10, 1), (1, 20), (10, 2), (2, 20)]);
+        assert!(impact(&t, &[CLIENT], &[SERVER], Fault::RemoveLink(1, 20)).is_safe());
+    }
+
+    #[test]
+    fn unsafe_drones_and_links() {
+        let t = topology(&[(10, 1), (1, 20), (1, 2), (2, 20)]);
+        let (drones, links) = unsafe_faults(&t, &[CLIENT], &[SERVER], &[1, 2]);
+        assert_eq!(drones, HashSet::from([1]));
+        assert_eq!(links, HashSet::from([(1, 10)]));
+    }
+
+    #[test]
+    fn delivery_probability_of_a_path() {
+        let pdr = |id| match id {
+            1 => Some(0.5),
+            2 => Some(0.0),
+            _ => None,
+        };
+        let p = delivery_probability(&[10, 1, 2, 20], pdr);
+        assert!((p - 0.5).abs() < 1e-9);
+    }
+}
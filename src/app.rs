@@ -1,2 +1,7 @@
+mod bulk;
+mod chaos;
+mod export;
+mod layout;
 mod reset;
+mod scenario;
 pub mod simulation_controller_ui;
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use eframe::egui::Context;
+
+use wg_2024::network::NodeId;
+
+use crate::app::simulation_controller_ui::{NodeWindowState, SimulationControllerUI};
+use crate::bulk::CrashSequence;
+use crate::ui_components;
+use crate::ui_components::bulk_window::BulkRequest;
+
+impl SimulationControllerUI {
+    /// spawns the window of the bulk actions and applies the one asked by
+    /// the user to every selected drone.
+    pub(crate) fn spawn_bulk_window(&mut self, ctx: &Context) {
+        // forget the sequence once every drone crashed
+        if self
+            .crash_sequence
+            .as_ref()
+            .is_some_and(CrashSequence::is_finished)
+        {
+            self.stop_crash_sequence();
+        }
+        let binding = self.simulation_data_ref.clone().unwrap();
+        let mut mutex = binding.lock().unwrap();
+        // crashed drones can't be selected anymore
+        let alive = mutex.sc.get_drone_ids();
+        self.selection.drones.retain(|id| alive.contains(id));
+        let drones: BTreeMap<NodeId, String> = self
+            .nodes
+            .iter()
+            .filter_map(|(&id, state)| match state {
+                NodeWindowState::Drone(_, dws) if alive.contains(&id) => {
+                    Some((id, dws.name.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let safety = self.safety.unsafe_faults(&mutex);
+        let request = ui_components::bulk_window::spawn(
+            ctx,
+            &safety,
+            &mut self.bulk,
+            &mut self.selection,
+            &drones,
+            self.crash_sequence.is_some(),
+        );
+        match request {
+            Some(BulkRequest::SetPdr(pdr)) => {
+                for &id in &self.selection.drones {
+                    let action = mutex.pdr_change(id, pdr);
+                    _ = mutex.apply_action(action);
+                }
+            }
+            Some(BulkRequest::ClearLogs) => {
+                for &id in &self.selection.drones {
+                    mutex.clear_log(id);
+                }
+            }
+            Some(BulkRequest::Crash(order, delay)) => {
+                drop(mutex);
+                self.crash_sequence = Some(CrashSequence::spawn(order, delay, &binding));
+            }
+            Some(BulkRequest::StopCrashing) => {
+                drop(mutex);
+                self.stop_crash_sequence();
+            }
+            None => {}
+        }
+    }
+
+    /// stops crashing the selected drones, if they are being crashed.
+    pub(crate) fn stop_crash_sequence(&mut self) {
+        if let Some(sequence) = self.crash_sequence.take() {
+            sequence.kill();
+        }
+    }
+}
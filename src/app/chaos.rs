@@ -0,0 +1,40 @@
+use eframe::egui::Context;
+
+use crate::app::simulation_controller_ui::SimulationControllerUI;
+use crate::chaos::ChaosThread;
+use crate::ui_components;
+use crate::ui_components::chaos_window::ChaosRequest;
+
+impl SimulationControllerUI {
+    /// spawns the window of the chaos mode, starting or stopping it if
+    /// requested.
+    pub(crate) fn spawn_chaos_window(&mut self, ctx: &Context) {
+        let binding = self.simulation_data_ref.clone().unwrap();
+        let mutex = binding.lock().unwrap();
+        let request = ui_components::chaos_window::spawn(ctx, &mutex, &mut self.chaos);
+        drop(mutex);
+        match request {
+            Some(ChaosRequest::Start) => self.start_chaos(),
+            Some(ChaosRequest::Stop) => self.stop_chaos(),
+            None => {}
+        }
+    }
+
+    /// stops the chaos mode, if running, and starts it again with the
+    /// current settings.
+    fn start_chaos(&mut self) {
+        self.stop_chaos();
+        let data_ref = self.simulation_data_ref.clone().unwrap();
+        data_ref.lock().unwrap().chaos_reports.clear();
+        let config = self.chaos.config.clone();
+        self.chaos.last_seed = Some(config.seed);
+        self.chaos.thread = Some(ChaosThread::spawn(config, &data_ref));
+    }
+
+    /// stops injecting faults, if the chaos mode is running.
+    pub(crate) fn stop_chaos(&mut self) {
+        if let Some(thread) = self.chaos.thread.take() {
+            thread.kill();
+        }
+    }
+}
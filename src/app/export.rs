@@ -0,0 +1,24 @@
+use crate::app::simulation_controller_ui::SimulationControllerUI;
+use crate::export;
+
+impl SimulationControllerUI {
+    /// lets the user pick a directory and exports the events and the
+    /// statistics of the current simulation there. The result is stored for
+    /// the ui to show.
+    pub fn open_export(&mut self) {
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(dir) = self
+            .topology_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(dir) = dialog.pick_folder() else {
+            return;
+        };
+        let binding = self.simulation_data_ref.clone().unwrap();
+        let mutex = binding.lock().unwrap();
+        self.export_result = Some(export::export(&mutex, &dir).map(|()| dir));
+    }
+}
@@ -0,0 +1,128 @@
+use eframe::egui::{Button, Color32, ComboBox, Ui};
+use petgraph::graph::NodeIndex;
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+use crate::app::simulation_controller_ui::SimulationControllerUI;
+use crate::layout::{Layout, SavedLayout};
+
+impl SimulationControllerUI {
+    /// spawns the layout selector and the buttons that save and reset the
+    /// node positions. Choosing a layout, or clicking "Arrange nodes",
+    /// places the nodes again.
+    pub(crate) fn spawn_layout_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Layout:");
+            let previous = self.layout;
+            ComboBox::from_id_salt("layout")
+                .selected_text(self.layout.to_string())
+                .show_ui(ui, |ui| {
+                    for layout in Layout::ALL {
+                        ui.selectable_value(&mut self.layout, layout, layout.to_string());
+                    }
+                });
+            if self.layout != previous || ui.button("Arrange nodes").clicked() {
+                self.layout_pending = true;
+            }
+            ui.separator();
+            if ui
+                .button("Save layout")
+                .on_hover_text("Restore the current positions after a reset")
+                .clicked()
+            {
+                self.save_layout();
+            }
+            if ui
+                .add_enabled(self.saved_layout.is_some(), Button::new("Reset layout"))
+                .on_hover_text("Forget the saved positions and arrange the nodes again")
+                .clicked()
+            {
+                self.reset_layout();
+            }
+            match &self.layout_file_result {
+                Some(Ok(message)) => {
+                    ui.colored_label(Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(Color32::LIGHT_RED, e.to_string());
+                }
+                None => {}
+            }
+        });
+    }
+
+    /// places the nodes of the topology graph with the selected layout. Right
+    /// after a reset, the nodes with a saved position are placed there instead.
+    pub(crate) fn apply_layout(&mut self) {
+        let nodes: Vec<(NodeId, NodeType)> = self
+            .graph
+            .nodes_iter()
+            .map(|(_, node)| *node.payload())
+            .collect();
+        let edges: Vec<(NodeId, NodeId)> = {
+            let binding = self.simulation_data_ref.clone().unwrap();
+            let mutex = binding.lock().unwrap();
+            mutex
+                .sc
+                .get_topology()
+                .all_edges()
+                .map(|(a, b, ())| (a, b))
+                .collect()
+        };
+        let mut positions = self.layout.compute(&nodes, &edges);
+        if std::mem::take(&mut self.restore_saved_layout) {
+            if let Some(saved) = &self.saved_layout {
+                positions.extend(saved.positions());
+            }
+        }
+        for (id, pos) in positions {
+            let Some(&index) = self.graph_index_map.get(&id) else {
+                continue;
+            };
+            if let Some(node) = self.graph.node_mut(NodeIndex::new(index)) {
+                node.set_location(pos);
+            }
+        }
+    }
+
+    /// reads the layout saved for the current topology, to be restored by the
+    /// next layout. A layout that can't be read is reported and ignored.
+    pub(crate) fn load_saved_layout(&mut self) {
+        self.restore_saved_layout = true;
+        match SavedLayout::load(&self.topology_path) {
+            Ok(saved) => {
+                self.saved_layout = saved;
+                self.layout_file_result = None;
+            }
+            Err(e) => {
+                self.saved_layout = None;
+                self.layout_file_result = Some(Err(e));
+            }
+        }
+    }
+
+    /// saves the current node positions next to the topology file.
+    fn save_layout(&mut self) {
+        let saved = SavedLayout::new(
+            self.graph
+                .nodes_iter()
+                .map(|(_, node)| (node.payload().0, node.location())),
+        );
+        self.layout_file_result = Some(
+            saved
+                .save(&self.topology_path)
+                .map(|path| format!("Saved to {}", path.display())),
+        );
+        self.saved_layout = Some(saved);
+    }
+
+    /// deletes the saved node positions and places the nodes with the
+    /// selected layout.
+    fn reset_layout(&mut self) {
+        self.layout_file_result = Some(
+            SavedLayout::remove(&self.topology_path).map(|()| "Saved layout removed".to_string()),
+        );
+        self.saved_layout = None;
+        self.layout_pending = true;
+    }
+}
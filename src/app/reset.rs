@@ -1,139 +1,143 @@
 use crate::app::simulation_controller_ui::{
-    ClientWindowState, DroneWindowState, SimulationControllerUI,
+    AddDroneWindowState, ClientWindowState, DroneWindowState, ServerWindowState,
+    SimulationControllerUI,
 };
-use crate::receiver_threads;
-use crate::shared_data::{ClientStats, DroneStats, ServerStats, SimulationData};
-use crossbeam_channel::unbounded;
+use crate::cli::DroneMode;
+use crate::pdr_schedule::PdrScheduler;
+use crate::receiver_threads::ReceiverThreads;
+use crate::shared_data::SimulationData;
+use crate::topology;
+use crate::ui_components::selection::Selection;
 use drone_network::controller::SimulationController;
-use drone_network::network::{init_network, init_network_with_drone};
-use eframe::egui::Color32;
-use lockheedrustin_drone::LockheedRustin;
 use petgraph::graph::NodeIndex;
 use petgraph::graphmap::UnGraphMap;
 use petgraph::prelude::StableUnGraph;
-use std::collections::{HashMap, VecDeque};
-use std::fs;
-use std::mem::take;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use wg_2024::config::Config;
 use wg_2024::network::NodeId;
 use wg_2024::packet::NodeType;
 
 impl SimulationControllerUI {
-    /// resets the app with `LockheedRustin` drones.
-    /// # Panics
-    /// Will panic if the topology contained in config.toml violates the protocol.
+    /// resets the app with `LockheedRustin` drones. If the topology file is
+    /// invalid, the error is shown and the current simulation keeps running.
     pub fn reset_with_our_drone(&mut self) {
-        self.reset(false);
+        self.reset(DroneMode::LockheedRustin);
     }
 
-    /// resets the app with the drones bought during the fair.
-    /// # Panics
-    /// Will panic if the topology contained in config.toml violates the protocol.
+    /// resets the app with the drones bought during the fair. If the topology
+    /// file is invalid, the error is shown and the current simulation keeps running.
     pub fn reset_with_fair_drones(&mut self) {
-        self.reset(true);
+        self.reset(DroneMode::Fair);
     }
 
-    /// resets the app.
-    /// # Panics
-    /// Will panic if the topology contained in config.toml violates the protocol.
-    fn reset(&mut self, random_drones: bool) {
+    /// lets the user pick a topology file and resets the app onto it, keeping
+    /// the drones of the current simulation. Nothing happens if the dialog is
+    /// closed without choosing a file.
+    pub fn open_topology(&mut self) {
+        let mut dialog = rfd::FileDialog::new().add_filter("Topology", &["toml"]);
+        if let Some(dir) = self
+            .topology_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        if let Some(path) = dialog.pick_file() {
+            self.reset_onto(path, self.drone_mode);
+        }
+    }
+
+    /// opens the window for choosing the implementation of each drone of
+    /// the current topology file before resetting.
+    pub fn open_implementations_window(&mut self) {
+        let state = &mut self.implementations_window;
+        match topology::read_drone_ids(&self.topology_path) {
+            Ok(ids) => {
+                state.drone_ids = ids;
+                state.error = None;
+            }
+            Err(e) => {
+                state.drone_ids.clear();
+                state.error = Some(e);
+            }
+        }
+        state.choices = self.drone_implementations.clone();
+        state.drone_mode = self.drone_mode;
+        state.open = true;
+    }
+
+    /// resets the app with the implementations chosen in the window.
+    pub(crate) fn reset_with_chosen_implementations(&mut self) {
+        self.implementations_window.open = false;
+        self.drone_implementations = self.implementations_window.choices.clone();
+        self.reset(self.implementations_window.drone_mode);
+    }
+
+    /// resets the app with the current topology file.
+    pub(crate) fn reset(&mut self, drone_mode: DroneMode) {
+        self.reset_onto(self.topology_path.clone(), drone_mode);
+    }
+
+    /// resets the app with the topology stored at `path`. The topology is
+    /// validated before touching the current simulation: if it is invalid,
+    /// the error is stored for the ui to show and nothing else changes.
+    fn reset_onto(&mut self, path: PathBuf, drone_mode: DroneMode) {
+        let sc = match topology::load_simulation_controller(
+            &path,
+            drone_mode,
+            &self.drone_implementations,
+        ) {
+            Ok(sc) => sc,
+            Err(e) => {
+                self.topology_error = Some((path, e));
+                return;
+            }
+        };
+        self.topology_error = None;
+        self.topology_path = path;
+        self.drone_mode = drone_mode;
+
+        self.stop_scenario();
+        self.stop_crash_sequence();
+        self.stop_chaos();
         self.kill_old_receiving_threads();
         // delete all file windows
         self.files.clear();
 
-        let sc = Self::get_simulation_controller(random_drones);
         self.reset_ids(&sc);
-
-        // new shared data
-        let logs = self.get_new_logs();
-        let drone_stats = self.get_new_drone_stats();
-        let client_stats = self.get_new_client_stats();
-        let server_stats = self.get_new_server_stats();
-
         self.reset_graph(&sc);
-
-        // create channels
-        let drone_receiver = sc.get_drone_recv();
-        let client_receiver = sc.get_client_recv();
-        let server_receiver = sc.get_server_recv();
-
-        let (kill_client_send, kill_client_recv) = unbounded();
-        let (kill_server_send, kill_server_recv) = unbounded();
-        let (kill_drone_send, kill_drone_recv) = unbounded();
-        self.kill_senders.push(kill_client_send);
-        self.kill_senders.push(kill_server_send);
-        self.kill_senders.push(kill_drone_send);
+        self.load_saved_layout();
 
         // create shared data
-        self.simulation_data_ref = Some(Arc::new(Mutex::new(SimulationData::new(
+        let data_ref = Arc::new(Mutex::new(SimulationData::new(
             sc,
-            logs,
-            drone_stats,
-            client_stats,
-            server_stats,
-            self.ctx.clone(),
-        ))));
+            self.log_capacity,
+            self.timeline_capacity,
+            Box::new(self.ctx.clone()),
+        )));
 
         // spawn receiving threads
-        let arc_clone = self.simulation_data_ref.clone().unwrap();
-        let handle = std::thread::spawn(move || {
-            let arc = arc_clone;
-            let event_recv = drone_receiver;
-            let kill_recv = kill_drone_recv;
-            receiver_threads::drone_receiver_loop(&arc, &event_recv, &kill_recv);
-        });
-        self.handles.push(handle);
-
-        let arc_clone = self.simulation_data_ref.clone().unwrap();
-        let handle = std::thread::spawn(move || {
-            let arc = arc_clone;
-            let event_recv = client_receiver;
-            let kill_recv = kill_client_recv;
-            receiver_threads::client_receiver_loop(&arc, &event_recv, &kill_recv);
-        });
-        self.handles.push(handle);
-
-        let arc_clone = self.simulation_data_ref.clone().unwrap();
-        let handle = std::thread::spawn(move || {
-            let arc = arc_clone;
-            let event_recv = server_receiver;
-            let kill_recv = kill_server_recv;
-            receiver_threads::server_receiver_loop(&arc, &event_recv, &kill_recv);
-        });
-        self.handles.push(handle);
+        self.receiver_threads = Some(ReceiverThreads::spawn(&data_ref));
+        self.pdr_scheduler = Some(PdrScheduler::spawn(&data_ref));
+        self.simulation_data_ref = Some(data_ref);
     }
 
-    /// kills receiving threads from previous iteration of the simulation.
+    /// kills receiving threads and the PDR scheduler from previous iteration
+    /// of the simulation.
     fn kill_old_receiving_threads(&mut self) {
-        // kill old receiving threads
-        for s in &self.kill_senders {
-            s.send(())
-                .expect("Error in sending kill message to receiving threads");
-        }
-        let handles = take(&mut self.handles);
-        for h in handles {
-            h.join().expect("Error in joining receiving threads");
+        if let Some(threads) = self.receiver_threads.take() {
+            threads.kill();
         }
-        self.handles.clear();
-        self.kill_senders.clear();
-    }
-
-    /// gets a new `SimulationController`.
-    fn get_simulation_controller(random_drones: bool) -> SimulationController {
-        let file_str = fs::read_to_string("config.toml").unwrap();
-        let config: Config = toml::from_str(&file_str).unwrap();
-        if random_drones {
-            init_network(&config).unwrap()
-        } else {
-            init_network_with_drone::<LockheedRustin>(&config, "Lockheed Rustin".to_string())
-                .unwrap()
+        if let Some(scheduler) = self.pdr_scheduler.take() {
+            scheduler.kill();
         }
     }
 
     /// reset the app's information about nodes, given a new `SimulationController`.
     fn reset_ids(&mut self, sc: &SimulationController) {
         self.nodes.clear();
+        self.add_drone = AddDroneWindowState::default();
+        self.selection = Selection::default();
         for id in sc.get_drone_ids() {
             self.nodes.insert(
                 id,
@@ -143,6 +147,7 @@ impl SimulationControllerUI {
                         name: sc.get_group_name(id).unwrap().to_string(),
                         pdr_slider: sc.get_pdr(id).unwrap(),
                         add_link_selected_id: None,
+                        ..DroneWindowState::default()
                     },
                 ),
             );
@@ -159,54 +164,20 @@ impl SimulationControllerUI {
         for id in sc.get_server_ids() {
             self.nodes.insert(
                 id,
-                crate::app::simulation_controller_ui::NodeWindowState::Server(false),
+                crate::app::simulation_controller_ui::NodeWindowState::Server(
+                    false,
+                    ServerWindowState::default(),
+                ),
             );
         }
     }
 
-    /// returns a new struct for storing logs about nodes. Assumes self has updated
-    /// information about nodes.
-    fn get_new_logs(&self) -> HashMap<NodeId, VecDeque<(String, Color32)>> {
-        let mut logs = HashMap::new();
-        for &id in self.nodes.keys() {
-            logs.insert(id, VecDeque::new());
-        }
-        logs
-    }
-
-    /// returns a new struct for storing stats about drones. Assumes self has updated
-    /// information about nodes.
-    fn get_new_drone_stats(&self) -> HashMap<NodeId, DroneStats> {
-        let mut drone_stats = HashMap::new();
-        for drone_id in self.get_ids(NodeType::Drone) {
-            drone_stats.insert(drone_id, DroneStats::default());
-        }
-        drone_stats
-    }
-
-    /// returns a new struct for storing stats about clients. Assumes self has updated
-    /// information about nodes.
-    fn get_new_client_stats(&self) -> HashMap<NodeId, ClientStats> {
-        let mut client_stats = HashMap::new();
-        for client_id in self.get_ids(NodeType::Client) {
-            client_stats.insert(client_id, ClientStats::default());
-        }
-        client_stats
-    }
-
-    /// returns a new struct for storing stats about servers. Assumes self has updated
-    /// information about nodes.
-    fn get_new_server_stats(&self) -> HashMap<NodeId, ServerStats> {
-        let mut server_stats = HashMap::new();
-        for server_id in self.get_ids(NodeType::Server) {
-            server_stats.insert(server_id, ServerStats::default());
-        }
-        server_stats
-    }
-
     /// reset the app's topology graph, given a new `SimulationController`.
     fn reset_graph(&mut self, sc: &SimulationController) {
         self.graph_cache_cleared = false;
+        self.layout_pending = true;
+        self.edge_menu = None;
+        self.pending_fault = None;
 
         let sc_graph: &UnGraphMap<NodeId, ()> = sc.get_topology();
 
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use crate::app::simulation_controller_ui::{ScenarioState, SimulationControllerUI};
+use crate::scenario::{Scenario, ScenarioThread};
+
+impl SimulationControllerUI {
+    /// lets the user pick a scenario file and runs it on the current
+    /// simulation. Nothing happens if the dialog is closed without choosing a file.
+    pub fn open_scenario(&mut self) {
+        let mut dialog = rfd::FileDialog::new().add_filter("Scenario", &["toml"]);
+        if let Some(dir) = self
+            .topology_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        if let Some(path) = dialog.pick_file() {
+            self.start_scenario(path);
+        }
+    }
+
+    /// stops the running scenario, if any, and runs the one stored at `path`
+    /// on the current simulation. Errors in the file are stored for the ui to show.
+    pub(crate) fn start_scenario(&mut self, path: PathBuf) {
+        self.stop_scenario();
+        let (total_steps, error, thread) = match Scenario::load(&path) {
+            Ok(scenario) => {
+                let total_steps = scenario.steps.len();
+                let data_ref = self.simulation_data_ref.clone().unwrap();
+                data_ref.lock().unwrap().scenario_reports.clear();
+                let thread = ScenarioThread::spawn(scenario, &data_ref);
+                (total_steps, None, Some(thread))
+            }
+            Err(e) => (0, Some(e), None),
+        };
+        self.scenario = Some(ScenarioState {
+            path,
+            total_steps,
+            error,
+            window_open: true,
+            thread,
+        });
+    }
+
+    /// stops the running scenario, if any.
+    pub(crate) fn stop_scenario(&mut self) {
+        if let Some(thread) = self.scenario.as_mut().and_then(|s| s.thread.take()) {
+            thread.kill();
+        }
+    }
+}
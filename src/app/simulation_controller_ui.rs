@@ -1,11 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::thread::JoinHandle;
+use std::time::Duration;
 
-use crossbeam_channel::Sender;
 use eframe::egui::{
-    CentralPanel, Color32, Context, CursorIcon, Frame, Label, RichText, Sense, SidePanel,
-    TextureHandle, TopBottomPanel, Ui, Vec2,
+    CentralPanel, Color32, ColorImage, Context, CursorIcon, Frame, Key, KeyboardShortcut, Label,
+    LayerId, Modifiers, Order, Pos2, RichText, ScrollArea, Sense, SidePanel, TextureFilter,
+    TextureHandle, TextureOptions, TopBottomPanel, Ui, Vec2,
 };
 use eframe::CreationContext;
 use egui_graphs::{
@@ -19,11 +20,33 @@ use petgraph::graph::{DefaultIx, NodeIndex};
 use wg_2024::network::NodeId;
 use wg_2024::packet::NodeType;
 
-use crate::shared_data::SimulationData;
+use crate::analysis::Fault;
+use crate::bulk::CrashSequence;
+use crate::chaos::{ChaosConfig, ChaosThread};
+use crate::cli::{Args, DroneMode};
+use crate::layout::{Layout, LayoutFileError, SavedLayout};
+use crate::pdr_schedule::{PdrSchedule, PdrScheduler};
+use crate::receiver_threads::ReceiverThreads;
+use crate::scenario::{ScenarioError, ScenarioThread};
+use crate::shared_data::{ReceivedFile, RepaintNotifier, SessionKey, SimulationData};
+use crate::topology::{DroneImplementation, TopologyError};
 use crate::ui_components;
 use crate::ui_components::client_window::{CommunicationChoice, ContentChoice, MessageChoice};
 use crate::ui_components::custom_edge::EdgeShape;
 use crate::ui_components::custom_node::NodeShape;
+use crate::ui_components::edge_menu::EdgeMenu;
+use crate::ui_components::fault_safety::{FaultSource, PendingFault, SafetyOverlay};
+use crate::ui_components::heatmap::HeatmapState;
+use crate::ui_components::history_window::HistoryRequest;
+use crate::ui_components::logs::LogFilter;
+use crate::ui_components::packet_animation::AnimationState;
+use crate::ui_components::path_overlay::PathOverlay;
+use crate::ui_components::selection::Selection;
+use crate::ui_components::timeline::TimelineState;
+
+/// offset from the center of its neighbors of a node added to the topology
+/// graph at runtime, so that it doesn't cover them.
+const NEW_NODE_OFFSET: Vec2 = Vec2::new(20.0, 20.0);
 
 /// ui state information about each node. The boolean represents if the window
 /// associated to the node is open or not.
@@ -31,7 +54,7 @@ use crate::ui_components::custom_node::NodeShape;
 pub(crate) enum NodeWindowState {
     Drone(bool, DroneWindowState),
     Client(bool, ClientWindowState),
-    Server(bool),
+    Server(bool, ServerWindowState),
 }
 
 /// window state information about each client.
@@ -43,6 +66,19 @@ pub struct ClientWindowState {
     pub server_destination_id: Option<NodeId>,
     pub client_destination_id: Option<NodeId>,
     pub text_input: String,
+    pub log_filter: LogFilter,
+    /// session whose trace is shown, opened from the log.
+    pub traced_session: Option<SessionKey>,
+}
+
+/// window state information about each server.
+#[derive(Default, Debug)]
+pub struct ServerWindowState {
+    pub log_filter: LogFilter,
+    /// session whose trace is shown, opened from the log.
+    pub traced_session: Option<SessionKey>,
+    pub add_link_selected_id: Option<NodeId>,
+    pub remove_link_selected_id: Option<NodeId>,
 }
 
 /// window state information about each drone.
@@ -50,7 +86,77 @@ pub struct ClientWindowState {
 pub struct DroneWindowState {
     pub name: String,
     pub pdr_slider: f32,
+    /// PDR of the drone when the slider was last synced with it
+    pub pdr_synced: Option<f32>,
     pub add_link_selected_id: Option<NodeId>,
+    pub remove_link_selected_id: Option<NodeId>,
+    /// schedule being edited, started on request
+    pub pdr_schedule: PdrSchedule,
+    /// samples of the edited schedule, with the schedule they were computed
+    /// for, so that they are only computed again when it is edited
+    pub pdr_preview: Option<(PdrSchedule, Vec<f32>)>,
+    pub log_filter: LogFilter,
+}
+
+/// state information about the window for adding a drone.
+#[derive(Default, Debug)]
+pub struct AddDroneWindowState {
+    pub open: bool,
+    pub id: NodeId,
+    pub pdr: f32,
+    pub neighbors: BTreeSet<NodeId>,
+    pub implementation: DroneImplementation,
+    /// reason why the last drone could not be spawned
+    pub error: Option<TopologyError>,
+}
+
+/// state information about the window for choosing the implementation of
+/// each drone before resetting.
+#[derive(Default, Debug)]
+pub struct ImplementationsWindowState {
+    pub open: bool,
+    /// drones of the topology file
+    pub drone_ids: Vec<NodeId>,
+    /// implementations chosen for single drones
+    pub choices: BTreeMap<NodeId, DroneImplementation>,
+    /// drones used for the others
+    pub drone_mode: DroneMode,
+    /// reason why the drones of the topology file could not be read
+    pub error: Option<TopologyError>,
+}
+
+/// state information about the window of the bulk actions on the selected
+/// drones.
+#[derive(Default, Debug)]
+pub struct BulkWindowState {
+    pub open: bool,
+    pub pdr: f32,
+    /// group whose drones can be added to the selection
+    pub group: Option<String>,
+    /// order in which the selected drones are crashed
+    pub crash_order: Vec<NodeId>,
+    /// seconds between two crashes
+    pub crash_delay: f64,
+}
+
+/// state information about the last scenario that was started.
+pub struct ScenarioState {
+    pub path: PathBuf,
+    pub total_steps: usize,
+    pub error: Option<ScenarioError>,
+    pub window_open: bool,
+    pub thread: Option<ScenarioThread>,
+}
+
+/// state information about the chaos mode.
+#[derive(Default)]
+pub struct ChaosState {
+    pub window_open: bool,
+    /// settings of the next run
+    pub config: ChaosConfig,
+    /// seed of the running or last run
+    pub last_seed: Option<u64>,
+    pub thread: Option<ChaosThread>,
 }
 
 /// enum for representing the app's sections.
@@ -58,6 +164,8 @@ pub struct DroneWindowState {
 pub(crate) enum Section {
     Control,
     Topology,
+    Timeline,
+    Dashboard,
 }
 
 /// struct for storing a content file's data.
@@ -72,14 +180,64 @@ pub struct ContentFile {
     pub file: ContentFileType,
 }
 
+impl ContentFile {
+    /// loads a file assembled by a client, turning images into textures.
+    fn load(ctx: &Context, received: ReceivedFile) -> Self {
+        let image = if infer::is_image(&received.bytes) {
+            image::load_from_memory(&received.bytes).ok()
+        } else {
+            None
+        };
+        let file = match image {
+            Some(image) => {
+                let size = [image.width() as usize, image.height() as usize];
+                let rgba = image.to_rgba8();
+                let color_image = ColorImage::from_rgba_unmultiplied(size, &rgba);
+
+                let opt = TextureOptions {
+                    magnification: TextureFilter::Nearest,
+                    minification: TextureFilter::Nearest,
+                    ..TextureOptions::default()
+                };
+
+                ContentFileType::Image(ctx.load_texture(&received.name, color_image, opt))
+            }
+            None => ContentFileType::Text(String::from_utf8_lossy(&received.bytes).to_string()),
+        };
+        Self {
+            name: received.name,
+            file,
+        }
+    }
+}
+
+impl RepaintNotifier for Context {
+    fn request_repaint(&self) {
+        Context::request_repaint(self);
+    }
+}
+
 /// Main app struct.
 pub struct SimulationControllerUI {
     /// menu section
     pub(crate) section: Section,
+    /// simulation settings
+    pub(crate) topology_path: PathBuf,
+    pub(crate) drone_mode: DroneMode,
+    /// implementations chosen for single drones, used by every reset
+    pub(crate) drone_implementations: BTreeMap<NodeId, DroneImplementation>,
+    pub(crate) log_capacity: usize,
+    pub(crate) timeline_capacity: Option<usize>,
+    /// last topology file that could not be loaded, with the reason
+    pub(crate) topology_error: Option<(PathBuf, TopologyError)>,
+    /// last scenario that was started
+    pub(crate) scenario: Option<ScenarioState>,
+    /// directory of the last export, or the reason it failed
+    pub(crate) export_result: Option<std::io::Result<PathBuf>>,
     /// handling receiver threads
     pub(crate) ctx: Context,
-    pub(crate) handles: Vec<JoinHandle<()>>,
-    pub(crate) kill_senders: Vec<Sender<()>>,
+    pub(crate) receiver_threads: Option<ReceiverThreads>,
+    pub(crate) pdr_scheduler: Option<PdrScheduler>,
     /// shared data
     pub(crate) simulation_data_ref: Option<Arc<Mutex<SimulationData>>>,
     pub(crate) nodes: HashMap<NodeId, NodeWindowState>,
@@ -88,10 +246,53 @@ pub struct SimulationControllerUI {
         egui_graphs::Graph<(NodeId, NodeType), (), Undirected, usize, NodeShape, EdgeShape>,
     pub(crate) graph_index_map: HashMap<NodeId, usize>,
     pub(crate) graph_cache_cleared: bool,
+    /// algorithm placing the nodes of the topology section
+    pub(crate) layout: Layout,
+    /// true if the layout must be applied in the next frame
+    pub(crate) layout_pending: bool,
+    /// node positions saved for the current topology, restored after a reset
+    pub(crate) saved_layout: Option<SavedLayout>,
+    /// true if the saved positions must be used by the next layout
+    pub(crate) restore_saved_layout: bool,
+    /// result of the last operation on the saved layout
+    pub(crate) layout_file_result: Option<Result<String, LayoutFileError>>,
+    /// filters of the timeline section
+    pub(crate) timeline: TimelineState,
+    /// packets animated in the topology section
+    pub(crate) animation: AnimationState,
+    /// traffic heatmap of the topology section
+    pub(crate) heatmap: HeatmapState,
+    /// menu of the edge right-clicked in the topology section
+    pub(crate) edge_menu: Option<EdgeMenu>,
+    /// highlighting of unsafe drones and links in the topology section
+    pub(crate) safety: SafetyOverlay,
+    /// paths between a client and a server in the topology section
+    pub(crate) paths: PathOverlay,
+    /// crash or link removal waiting to be confirmed
+    pub(crate) pending_fault: Option<PendingFault>,
+    /// window for adding a drone to the running simulation
+    pub(crate) add_drone: AddDroneWindowState,
+    /// window for choosing the implementation of each drone
+    pub(crate) implementations_window: ImplementationsWindowState,
+    /// drones selected for bulk actions
+    pub(crate) selection: Selection,
+    /// window of the bulk actions
+    pub(crate) bulk: BulkWindowState,
+    /// selected drones being crashed one at a time
+    pub(crate) crash_sequence: Option<CrashSequence>,
+    /// random faults injected in the simulation
+    pub(crate) chaos: ChaosState,
+    /// true if the window of the history of the actions is open
+    pub(crate) history_open: bool,
 }
 
 impl eframe::App for SimulationControllerUI {
     fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
+        ui_components::topology_error_window::spawn(ctx, &mut self.topology_error);
+        if self.simulation_data_ref.is_none() {
+            self.no_simulation_section(ctx);
+            return;
+        }
         self.menu_bar(ctx);
         match self.section {
             Section::Control => {
@@ -100,31 +301,94 @@ impl eframe::App for SimulationControllerUI {
             Section::Topology => {
                 self.topology_section(ctx);
             }
+            Section::Timeline => {
+                self.timeline_section(ctx);
+            }
+            Section::Dashboard => {
+                self.dashboard_section(ctx);
+            }
         }
+        self.spawn_fault_confirmation(ctx);
+        self.spawn_history_window(ctx);
     }
 }
 
 impl SimulationControllerUI {
-    /// Returns a new app.
-    /// # Panics
-    /// Will panic if the topology contained in config.toml violates the protocol.
-    pub fn new(cc: &CreationContext<'_>) -> Self {
+    /// Returns a new app, configured with the command line arguments.
+    pub fn new(cc: &CreationContext<'_>, args: &Args) -> Self {
         let mut res = Self {
             section: Section::Control,
+            topology_path: args.topology.clone(),
+            drone_mode: args.drones,
+            drone_implementations: BTreeMap::new(),
+            log_capacity: args.log_capacity,
+            timeline_capacity: args.timeline_capacity,
+            topology_error: None,
+            scenario: None,
+            export_result: None,
             ctx: cc.egui_ctx.clone(),
-            handles: Vec::default(),
-            kill_senders: Vec::default(),
+            receiver_threads: None,
+            pdr_scheduler: None,
             simulation_data_ref: None,
             nodes: HashMap::default(),
             files: vec![],
             graph: egui_graphs::Graph::from(&StableUnGraph::default()),
             graph_index_map: HashMap::default(),
             graph_cache_cleared: false,
+            layout: Layout::default(),
+            layout_pending: true,
+            saved_layout: None,
+            restore_saved_layout: false,
+            layout_file_result: None,
+            timeline: TimelineState::default(),
+            animation: AnimationState::default(),
+            heatmap: HeatmapState::default(),
+            edge_menu: None,
+            safety: SafetyOverlay::default(),
+            pending_fault: None,
+            paths: PathOverlay::default(),
+            add_drone: AddDroneWindowState::default(),
+            implementations_window: ImplementationsWindowState::default(),
+            selection: Selection::default(),
+            bulk: BulkWindowState::default(),
+            crash_sequence: None,
+            chaos: ChaosState::default(),
+            history_open: false,
         };
-        res.reset_with_fair_drones();
+        res.reset(args.drones);
+        if let Some(path) = &args.scenario {
+            // the scenario can't run if the topology was rejected
+            if res.simulation_data_ref.is_some() {
+                res.start_scenario(path.clone());
+            }
+        }
         res
     }
 
+    /// renders the page shown when no simulation could be started.
+    fn no_simulation_section(&mut self, ctx: &Context) {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("No simulation is running");
+            ui.add_space(5.0);
+            ui.label(format!(
+                "The topology file {} could not be loaded.",
+                self.topology_path.display()
+            ));
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                if ui.button("Open topology…").clicked() {
+                    self.open_topology();
+                }
+                if ui.button("Retry").clicked() {
+                    self.reset(self.drone_mode);
+                }
+                if ui.button("Quit app").clicked() {
+                    std::process::exit(0);
+                }
+            });
+        });
+    }
+
     /// renders the control section of the app.
     fn control_section(&mut self, ctx: &Context) {
         self.update_id_list();
@@ -135,6 +399,29 @@ impl SimulationControllerUI {
         for (open, fws) in &mut self.files {
             ui_components::file_window::spawn(ctx, open, fws);
         }
+        // scenario window
+        self.spawn_scenario_window(ctx);
+        // drone implementations window
+        if ui_components::implementations_window::spawn(ctx, &mut self.implementations_window) {
+            self.reset_with_chosen_implementations();
+        }
+        // add drone window
+        {
+            let mut sorted_node_ids = self.get_all_ids();
+            sorted_node_ids.sort_unstable();
+            let binding = self.simulation_data_ref.clone().unwrap();
+            let mut mutex = binding.lock().unwrap();
+            ui_components::add_drone_window::spawn(
+                ctx,
+                &mut mutex,
+                &sorted_node_ids,
+                &mut self.add_drone,
+            );
+        }
+        // bulk actions window
+        self.spawn_bulk_window(ctx);
+        // chaos mode window
+        self.spawn_chaos_window(ctx);
         // node windows
         CentralPanel::default().show(ctx, |_ui| {
             self.spawn_node_windows(ctx);
@@ -144,17 +431,63 @@ impl SimulationControllerUI {
     /// renders the topology section of the app.
     fn topology_section(&mut self, ctx: &Context) {
         self.update_graph();
+        self.update_animation(ctx);
         TopBottomPanel::bottom("top-panel").show(ctx, |ui| {
+            ui.add_space(2.0);
+            self.spawn_layout_controls(ui);
+            self.animation.spawn_controls(ui);
+            self.heatmap.spawn_controls(ui);
+            self.safety.spawn_controls(ui);
+            let mut clients = self.get_ids(NodeType::Client);
+            clients.sort_unstable();
+            let mut servers = self.get_ids(NodeType::Server);
+            servers.sort_unstable();
+            self.paths.spawn_controls(ui, &clients, &servers);
+            if !self.selection.drones.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} drones selected", self.selection.drones.len()));
+                    if ui.button("Bulk actions…").clicked() {
+                        self.bulk.open = true;
+                    }
+                    if ui.button("Clear selection").clicked() {
+                        self.selection.drones.clear();
+                    }
+                });
+            }
             ui.add_space(2.0);
             ui.label(
                 "Tip: use ctrl + mouse wheel to zoom in/out. \
-                You can move nodes around and pan the camera with the mouse cursor.",
+                You can move nodes around and pan the camera with the mouse cursor. \
+                Right-click a link to remove it. \
+                Shift-click or shift-drag around drones to select them for bulk actions.",
             );
             ui.add_space(2.0);
         });
+        // spawned before the graph, so that the click opening a menu doesn't
+        // close it right away
+        if let Some(fault) = ui_components::edge_menu::spawn(ctx, &mut self.edge_menu) {
+            let binding = self.simulation_data_ref.clone().unwrap();
+            let mutex = binding.lock().unwrap();
+            self.request_fault(&mutex, fault);
+        }
+        // holding shift selects drones instead of moving them or the camera
+        let (shift, pointer, pressed, down) = ctx.input(|i| {
+            (
+                i.modifiers.shift,
+                i.pointer.interact_pos(),
+                i.pointer.primary_pressed(),
+                i.pointer.primary_down(),
+            )
+        });
         CentralPanel::default()
             .frame(Frame::default().fill(Color32::from_rgb(27, 27, 27)))
             .show(ctx, |ui| {
+                if shift && pressed && ui.ui_contains_pointer() {
+                    self.selection.lasso_start = pointer;
+                }
+                let lasso = self.selection.lasso(pointer);
+                self.selection.update(ctx, lasso);
+
                 let mut grap_view = GraphView::<
                     (NodeId, NodeType),
                     (),
@@ -166,13 +499,56 @@ impl SimulationControllerUI {
                     LayoutRandom,
                 >::new(&mut self.graph)
                 .with_styles(&SettingsStyle::default().with_labels_always(true))
-                .with_interactions(&SettingsInteraction::default().with_dragging_enabled(true))
+                .with_interactions(&SettingsInteraction::default().with_dragging_enabled(!shift))
                 .with_navigations(
                     &SettingsNavigation::default()
                         .with_fit_to_screen_enabled(false)
-                        .with_zoom_and_pan_enabled(true),
+                        .with_zoom_and_pan_enabled(!shift),
                 );
-                ui.add(&mut grap_view);
+                let response = ui.add(&mut grap_view);
+                // nodes covered by a window can't be pointed at
+                let pointed = |id: Option<NodeId>| id.filter(|_| response.hovered());
+                if let Some(id) = pointed(ui_components::custom_node::take_clicked(ctx)) {
+                    if !shift {
+                        self.open_node_window(ctx, id);
+                    } else if self.get_ids(NodeType::Drone).contains(&id) {
+                        self.selection.toggle(id);
+                    }
+                }
+                let lassoed = ui_components::selection::take_lassoed(ctx);
+                if let Some(lasso) = lasso {
+                    if down {
+                        ui_components::selection::paint_lasso(ui, lasso);
+                    } else {
+                        let drones = self.get_ids(NodeType::Drone);
+                        self.selection.finish_lasso(
+                            lasso,
+                            lassoed.into_iter().filter(|id| drones.contains(id)),
+                        );
+                    }
+                }
+                if let Some(id) = pointed(ui_components::custom_node::take_hovered(ctx)) {
+                    let binding = self.simulation_data_ref.clone().unwrap();
+                    let mutex = binding.lock().unwrap();
+                    response.clone().on_hover_ui_at_pointer(|ui| {
+                        ui_components::node_tooltip::spawn(ui, &mutex, id);
+                    });
+                }
+                if let Some(edge) = ui_components::custom_edge::take_right_clicked(ctx) {
+                    if let Some(pos) = response
+                        .contains_pointer()
+                        .then(|| ctx.pointer_interact_pos())
+                        .flatten()
+                    {
+                        self.edge_menu = Some(EdgeMenu { edge, pos });
+                    }
+                }
+                // the random layout runs again in the first frame after the
+                // cache is cleared, so the layout is applied after it
+                if self.layout_pending && self.graph_cache_cleared {
+                    self.layout_pending = false;
+                    self.apply_layout();
+                }
                 // clear the graph cache only once after resetting
                 if !self.graph_cache_cleared {
                     self.graph_cache_cleared = true;
@@ -188,6 +564,56 @@ impl SimulationControllerUI {
                     >::clear_cache(ui);
                 }
             });
+        // windows of the nodes clicked in the graph
+        self.spawn_node_windows(ctx);
+        self.spawn_bulk_window(ctx);
+    }
+
+    /// opens the window of a node, or brings it to the front if it is
+    /// already open.
+    fn open_node_window(&mut self, ctx: &Context, id: NodeId) {
+        let Some(state) = self.nodes.get_mut(&id) else {
+            return;
+        };
+        match state {
+            NodeWindowState::Drone(open, _)
+            | NodeWindowState::Client(open, _)
+            | NodeWindowState::Server(open, _) => *open = true,
+        }
+        ctx.move_to_top(LayerId::new(
+            Order::Middle,
+            ui_components::node_window_id(id),
+        ));
+    }
+
+    /// renders the timeline section of the app.
+    fn timeline_section(&mut self, ctx: &Context) {
+        self.update_id_list();
+        let mut sorted_node_ids = self.get_all_ids();
+        sorted_node_ids.sort_unstable();
+        CentralPanel::default().show(ctx, |ui| {
+            let binding = self.simulation_data_ref.clone().unwrap();
+            let mutex = binding.lock().unwrap();
+            ui_components::timeline::spawn(ui, &mutex, &sorted_node_ids, &mut self.timeline);
+        });
+    }
+
+    /// renders the dashboard section of the app.
+    fn dashboard_section(&mut self, ctx: &Context) {
+        CentralPanel::default().show(ctx, |ui| {
+            let binding = self.simulation_data_ref.clone().unwrap();
+            let mutex = binding.lock().unwrap();
+            // crashed drones are kept to show when they stopped forwarding
+            let mut drone_ids: Vec<NodeId> = mutex.drone_stats.keys().copied().collect();
+            drone_ids.sort_unstable();
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui_components::charts::spawn_dashboard(ui, &mutex, &drone_ids);
+                });
+        });
+        // the charts roll even when no event arrives
+        ctx.request_repaint_after(Duration::from_secs(1));
     }
 
     /// renders the menu bar for switching section.
@@ -202,6 +628,8 @@ impl SimulationControllerUI {
                 ui.horizontal(|ui| {
                     self.spawn_menu_element(ui, "Control", Section::Control);
                     self.spawn_menu_element(ui, "Topology", Section::Topology);
+                    self.spawn_menu_element(ui, "Timeline", Section::Timeline);
+                    self.spawn_menu_element(ui, "Dashboard", Section::Dashboard);
                 });
             });
     }
@@ -252,16 +680,83 @@ impl SimulationControllerUI {
                 let mut v = self.get_ids(NodeType::Drone);
                 v.sort_unstable();
                 for id in v {
-                    self.spawn_node_list_element(ui, id, "Drone");
+                    ui.horizontal(|ui| {
+                        let mut selected = self.selection.drones.contains(&id);
+                        if ui
+                            .checkbox(&mut selected, "")
+                            .on_hover_text("Select for bulk actions")
+                            .changed()
+                        {
+                            self.selection.toggle(id);
+                        }
+                        self.spawn_node_list_element(ui, id, "Drone");
+                    });
                 }
             });
+            if ui.button("Add drone…").clicked() {
+                self.add_drone.open = true;
+                let binding = self.simulation_data_ref.clone().unwrap();
+                let known_ids = binding.lock().unwrap().get_known_ids();
+                self.add_drone.id = ui_components::add_drone_window::first_free_id(&known_ids);
+                self.add_drone.error = None;
+            }
+            let label = match self.selection.drones.len() {
+                0 => "Bulk actions…".to_string(),
+                n => format!("Bulk actions… ({n} selected)"),
+            };
+            if ui.button(label).clicked() {
+                self.bulk.open = true;
+            }
             ui.separator();
+            if ui.button("History…").clicked() {
+                self.history_open = true;
+            }
+            ui.add_space(3.0);
             if ui.button("Clear all logs").clicked() {
                 let binding = self.simulation_data_ref.clone().unwrap();
                 let mut mutex = binding.lock().unwrap();
                 mutex.clear_all_logs();
             }
             ui.add_space(3.0);
+            ui.label(format!("Topology: {}", self.topology_path.display()));
+            if ui.button("Open topology…").clicked() {
+                self.open_topology();
+            }
+            ui.add_space(3.0);
+            if ui.button("Run scenario…").clicked() {
+                self.open_scenario();
+            }
+            if let Some(state) = &mut self.scenario {
+                if !state.window_open && ui.button("Show scenario").clicked() {
+                    state.window_open = true;
+                }
+            }
+            ui.add_space(3.0);
+            let label = if self.chaos.thread.is_some() {
+                "Chaos mode… (running)"
+            } else {
+                "Chaos mode…"
+            };
+            if ui.button(label).clicked() {
+                self.chaos.window_open = true;
+            }
+            ui.add_space(3.0);
+            if ui.button("Export…").clicked() {
+                self.open_export();
+            }
+            match &self.export_result {
+                Some(Ok(dir)) => {
+                    ui.colored_label(
+                        Color32::LIGHT_GREEN,
+                        format!("Exported to {}", dir.display()),
+                    );
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(Color32::LIGHT_RED, format!("Export failed: {e}"));
+                }
+                None => {}
+            }
+            ui.add_space(3.0);
             if ui.button("Reset simulation with\nfair drones").clicked() {
                 self.reset_with_fair_drones();
             }
@@ -273,12 +768,31 @@ impl SimulationControllerUI {
                 self.reset_with_our_drone();
             }
             ui.add_space(3.0);
+            if ui.button("Choose drones…").clicked() {
+                self.open_implementations_window();
+            }
+            ui.add_space(3.0);
             if ui.button("Quit app").clicked() {
                 std::process::exit(0);
             }
         });
     }
 
+    /// spawn the window of the last scenario, running it again if requested.
+    fn spawn_scenario_window(&mut self, ctx: &Context) {
+        let Some(state) = self.scenario.as_mut() else {
+            return;
+        };
+        let binding = self.simulation_data_ref.clone().unwrap();
+        let mutex = binding.lock().unwrap();
+        let run_again = ui_components::scenario_window::spawn(ctx, &mutex, state);
+        drop(mutex);
+        if run_again {
+            let path = state.path.clone();
+            self.start_scenario(path);
+        }
+    }
+
     /// spawn windows for all nodes.
     pub fn spawn_node_windows(&mut self, ctx: &Context) {
         let mut sorted_node_ids = self.get_all_ids();
@@ -290,20 +804,26 @@ impl SimulationControllerUI {
         let mut sorted_server_ids: Vec<NodeId> = self.get_ids(NodeType::Server);
         sorted_server_ids.sort_unstable();
 
+        let mut sorted_drone_ids: Vec<NodeId> = self.get_ids(NodeType::Drone);
+        sorted_drone_ids.sort_unstable();
+
         let binding = self.simulation_data_ref.clone().unwrap();
         let mut mutex = binding.lock().unwrap();
 
+        let mut requested_fault = None;
         for id in self.get_all_ids() {
             match self.nodes.get_mut(&id).unwrap() {
                 NodeWindowState::Drone(open, state) => {
-                    ui_components::drone_window::spawn(
+                    if let Some(fault) = ui_components::drone_window::spawn(
                         ctx,
                         &mut mutex,
                         id,
                         &sorted_node_ids,
                         open,
                         state,
-                    );
+                    ) {
+                        requested_fault = Some(fault);
+                    }
                 }
                 NodeWindowState::Client(open, state) => {
                     ui_components::client_window::spawn(
@@ -316,11 +836,96 @@ impl SimulationControllerUI {
                         state,
                     );
                 }
-                NodeWindowState::Server(open) => {
-                    ui_components::server_window::spawn(ctx, &mut mutex, open, id);
+                NodeWindowState::Server(open, state) => {
+                    if let Some(fault) = ui_components::server_window::spawn(
+                        ctx,
+                        &mut mutex,
+                        open,
+                        id,
+                        &sorted_drone_ids,
+                        state,
+                    ) {
+                        requested_fault = Some(fault);
+                    }
                 }
             }
         }
+        if let Some(fault) = requested_fault {
+            self.request_fault(&mutex, fault, FaultSource::User);
+        }
+    }
+
+    /// asks the user to confirm a crash or a link removal, showing what it
+    /// would break.
+    fn request_fault(&mut self, data: &SimulationData, fault: Fault, source: FaultSource) {
+        self.pending_fault = Some(PendingFault {
+            fault,
+            impact: data.fault_impact(fault),
+            source,
+        });
+    }
+
+    /// spawns the window confirming the pending fault, and applies it if
+    /// the user confirms. An undo or a redo is only applied if it would
+    /// still cause the confirmed fault.
+    fn spawn_fault_confirmation(&mut self, ctx: &Context) {
+        if let Some((fault, source)) =
+            ui_components::fault_safety::spawn_confirmation(ctx, &mut self.pending_fault)
+        {
+            let binding = self.simulation_data_ref.clone().unwrap();
+            let mut mutex = binding.lock().unwrap();
+            match source {
+                FaultSource::User => _ = mutex.apply_fault(fault),
+                FaultSource::Undo if mutex.undo_fault() == Some(fault) => {
+                    mutex.undo();
+                }
+                FaultSource::Redo if mutex.redo_fault() == Some(fault) => {
+                    mutex.redo();
+                }
+                FaultSource::Undo | FaultSource::Redo => {}
+            }
+        }
+    }
+
+    /// undoes or redoes the last action, asking to confirm it first if it
+    /// crashes a drone or removes a link, like the same change made by hand.
+    fn handle_history_request(&mut self, data: &mut SimulationData, request: HistoryRequest) {
+        match request {
+            HistoryRequest::Undo => match data.undo_fault() {
+                Some(fault) => self.request_fault(data, fault, FaultSource::Undo),
+                None => {
+                    data.undo();
+                }
+            },
+            HistoryRequest::Redo => match data.redo_fault() {
+                Some(fault) => self.request_fault(data, fault, FaultSource::Redo),
+                None => {
+                    data.redo();
+                }
+            },
+        }
+    }
+
+    /// spawns the history of the actions, and undoes or redoes the last one
+    /// on ctrl + Z or ctrl + shift + Z unless a text field is being edited.
+    fn spawn_history_window(&mut self, ctx: &Context) {
+        let binding = self.simulation_data_ref.clone().unwrap();
+        let mut mutex = binding.lock().unwrap();
+        if ctx.memory(|m| m.focused().is_none()) {
+            let redo = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+            let undo = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+            // the undo shortcut also matches with shift, so redo is checked first
+            if ctx.input_mut(|i| i.consume_shortcut(&redo)) {
+                self.handle_history_request(&mut mutex, HistoryRequest::Redo);
+            } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+                self.handle_history_request(&mut mutex, HistoryRequest::Undo);
+            }
+        }
+        if let Some(request) =
+            ui_components::history_window::spawn(ctx, &mutex, &mut self.history_open)
+        {
+            self.handle_history_request(&mut mutex, request);
+        }
     }
 
     /// spawn a node list element for opening and closing node windows.
@@ -329,7 +934,7 @@ impl SimulationControllerUI {
 
         let mut drone_name = None;
         let open = match self.nodes.get_mut(&id).unwrap() {
-            NodeWindowState::Client(o, _) | NodeWindowState::Server(o) => o,
+            NodeWindowState::Client(o, _) | NodeWindowState::Server(o, _) => o,
             NodeWindowState::Drone(o, dws) => {
                 drone_name = Some(&dws.name);
                 o
@@ -366,7 +971,7 @@ impl SimulationControllerUI {
                         res.push(*id);
                     }
                 }
-                NodeWindowState::Server(_) => {
+                NodeWindowState::Server(_, _) => {
                     if node_type == NodeType::Server {
                         res.push(*id);
                     }
@@ -391,6 +996,48 @@ impl SimulationControllerUI {
                 self.nodes.remove(&id);
             }
         }
+        self.selection.drones.retain(|id| sc_drone_ids.contains(id));
+        // add drones spawned at runtime
+        for id in sc_drone_ids {
+            self.nodes.entry(id).or_insert_with(|| {
+                NodeWindowState::Drone(
+                    false,
+                    DroneWindowState {
+                        name: mutex.sc.get_group_name(id).unwrap_or_default().to_string(),
+                        pdr_slider: mutex.sc.get_pdr(id).unwrap_or_default(),
+                        ..DroneWindowState::default()
+                    },
+                )
+            });
+        }
+    }
+
+    /// adds a node to the topology graph, placed near its neighbors.
+    fn add_graph_node(&mut self, id: NodeId, node_type: NodeType) {
+        let neighbors: Vec<NodeId> = {
+            let binding = self.simulation_data_ref.clone().unwrap();
+            let mutex = binding.lock().unwrap();
+            mutex.sc.get_topology().neighbors(id).collect()
+        };
+        let locations: Vec<Pos2> = neighbors
+            .iter()
+            .filter_map(|n| self.graph_index_map.get(n))
+            .filter_map(|&index| self.graph.node(NodeIndex::new(index)))
+            .map(|node| node.location())
+            .collect();
+        let location = if locations.is_empty() {
+            Pos2::ZERO
+        } else {
+            let sum = locations
+                .iter()
+                .fold(Vec2::ZERO, |acc, p| acc + p.to_vec2());
+            (sum / locations.len() as f32).to_pos2() + NEW_NODE_OFFSET
+        };
+        let index = self.graph.add_node((id, node_type));
+        if let Some(node) = self.graph.node_mut(index) {
+            node.set_location(location);
+        }
+        self.graph_index_map.insert(id, index.index());
     }
 
     /// updates the graph rendered in the topology section.
@@ -409,10 +1056,31 @@ impl SimulationControllerUI {
                 self.graph_index_map.remove(&node_id);
             }
         }
-        // add new edges
+        // add drones spawned at runtime, next to their neighbors
+        for id in current_drone_ids {
+            if !self.graph_index_map.contains_key(&id) {
+                self.add_graph_node(id, NodeType::Drone);
+            }
+        }
         let binding = self.simulation_data_ref.clone().unwrap();
         let mutex = binding.lock().unwrap();
-        let current_edges: Vec<(NodeId, NodeId, _)> = mutex.sc.get_topology().all_edges().collect();
+        let topology = mutex.sc.get_topology();
+        // delete removed edges
+        let removed_edges: Vec<_> = self
+            .graph
+            .g
+            .edge_indices()
+            .filter(|edge| {
+                let (i1, i2) = self.graph.g.edge_endpoints(*edge).unwrap();
+                let (id1, id2) = (self.graph.g[i1].payload().0, self.graph.g[i2].payload().0);
+                !topology.contains_edge(id1, id2)
+            })
+            .collect();
+        for edge in removed_edges {
+            self.graph.remove_edge(edge);
+        }
+        // add new edges
+        let current_edges: Vec<(NodeId, NodeId, _)> = topology.all_edges().collect();
 
         for (id1, id2, ()) in current_edges {
             let i1 = NodeIndex::from(*self.graph_index_map.get(&id1).unwrap());
@@ -424,6 +1092,20 @@ impl SimulationControllerUI {
         }
     }
 
+    /// animates the packets that moved since the last frame and updates the
+    /// heatmap.
+    fn update_animation(&mut self, ctx: &Context) {
+        let binding = self.simulation_data_ref.clone().unwrap();
+        let mut mutex = binding.lock().unwrap();
+        let movements = mutex.take_movements();
+        let elapsed = mutex.elapsed();
+        self.heatmap.update(ctx, &mutex);
+        self.safety.update(ctx, &mutex);
+        self.paths.update(ctx, &mutex);
+        drop(mutex);
+        self.animation.update(ctx, movements, elapsed);
+    }
+
     /// checks for new files to display.
     fn update_files(&mut self) {
         // delete all files with closed windows
@@ -432,7 +1114,7 @@ impl SimulationControllerUI {
         let mut mutex = binding.lock().unwrap();
         // take all files from shared data
         while let Some(file) = mutex.files.pop() {
-            self.files.push((true, file));
+            self.files.push((true, ContentFile::load(&self.ctx, file)));
         }
     }
 }
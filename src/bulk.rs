@@ -0,0 +1,78 @@
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crossbeam_channel::{select_biased, unbounded, Receiver, Sender};
+use wg_2024::network::NodeId;
+
+use crate::shared_data::{HistoryAction, LogEntry, LogKind, SimulationData};
+
+/// The thread that crashes a selection of drones one at a time.
+pub struct CrashSequence {
+    handle: JoinHandle<()>,
+    kill_sender: Sender<()>,
+}
+
+impl CrashSequence {
+    /// Spawns a thread that crashes the drones in the given order, waiting
+    /// `delay` between two crashes. The first one is crashed right away.
+    pub fn spawn(
+        order: Vec<NodeId>,
+        delay: Duration,
+        data_ref: &Arc<Mutex<SimulationData>>,
+    ) -> Self {
+        let (kill_sender, kill_recv) = unbounded();
+        let arc_clone = data_ref.clone();
+        let handle = std::thread::spawn(move || {
+            crash_loop(&order, delay, &arc_clone, &kill_recv);
+        });
+        Self {
+            handle,
+            kill_sender,
+        }
+    }
+
+    /// Returns true if every drone of the sequence was crashed.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Stops crashing drones and waits for the thread to terminate.
+    /// # Panics
+    /// Will panic if the thread has panicked.
+    pub fn kill(self) {
+        // the thread may have already finished and dropped the receiver
+        _ = self.kill_sender.send(());
+        self.handle
+            .join()
+            .expect("Error in joining the crash sequence thread");
+    }
+}
+
+fn crash_loop(
+    order: &[NodeId],
+    delay: Duration,
+    data_ref: &Arc<Mutex<SimulationData>>,
+    rec_kill: &Receiver<()>,
+) {
+    for (i, &id) in order.iter().enumerate() {
+        if i > 0 {
+            select_biased! {
+                recv(rec_kill) -> _ => {
+                    return;
+                }
+                default(delay) => {}
+            }
+        }
+        let mut data = data_ref.lock().unwrap();
+        if data.apply_action(HistoryAction::Crash(id)).is_ok() {
+            data.add_log(LogEntry::new(
+                id,
+                LogKind::ControllerAction,
+                format!("Crashed by bulk action ({}/{})", i + 1, order.len()),
+            ));
+        }
+        data.request_repaint();
+    }
+}
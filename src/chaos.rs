@@ -0,0 +1,372 @@
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{select_biased, unbounded, Receiver, Sender};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+use crate::analysis::Fault;
+use crate::shared_data::{HistoryAction, LogEntry, LogKind, Severity, SimulationData};
+
+/// How much the PDR of a drone is raised at most by a single fault.
+const MAX_PDR_RAISE: f32 = 0.2;
+/// Maximum number of drones a client can be linked to.
+const CLIENT_MAX_LINKS: usize = 2;
+
+/// Settings of the chaos mode.
+#[derive(Clone, Debug)]
+pub struct ChaosConfig {
+    /// the same seed injects the same faults in the same network.
+    pub seed: u64,
+    /// seconds between two faults.
+    pub interval: f64,
+    pub raise_pdr: bool,
+    pub remove_link: bool,
+    pub crash: bool,
+    pub add_link: bool,
+    /// PDR that raised drones never exceed.
+    pub max_pdr: f32,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            interval: 5.0,
+            raise_pdr: true,
+            remove_link: true,
+            crash: true,
+            add_link: true,
+            max_pdr: 0.5,
+        }
+    }
+}
+
+/// A fault injected by the chaos mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChaosFault {
+    RaisePdr {
+        drone: NodeId,
+        pdr: f32,
+    },
+    RemoveLink(NodeId, NodeId),
+    /// crash of a drone whose crash doesn't break any route.
+    Crash(NodeId),
+    AddLink(NodeId, NodeId),
+}
+
+/// A fault injected by the chaos mode, with its result.
+#[derive(Debug)]
+pub struct ChaosReport {
+    /// seconds since the chaos mode started.
+    pub at: f64,
+    pub fault: ChaosFault,
+    pub applied: bool,
+}
+
+impl ChaosFault {
+    /// Returns the node whose log records the fault.
+    #[must_use]
+    pub fn node(&self) -> NodeId {
+        match *self {
+            ChaosFault::RaisePdr { drone: id, .. }
+            | ChaosFault::RemoveLink(id, _)
+            | ChaosFault::Crash(id)
+            | ChaosFault::AddLink(id, _) => id,
+        }
+    }
+
+    /// Applies the fault to the simulation, without recording it in the
+    /// history. Returns true if it was applied.
+    pub fn apply(self, data: &mut SimulationData) -> bool {
+        let action = match self {
+            ChaosFault::RaisePdr { drone, pdr } => data.pdr_change(drone, pdr),
+            ChaosFault::RemoveLink(a, b) => HistoryAction::RemoveLink(a, b),
+            ChaosFault::Crash(id) => HistoryAction::Crash(id),
+            ChaosFault::AddLink(a, b) => HistoryAction::AddLink(a, b),
+        };
+        data.apply_action(action).is_ok()
+    }
+}
+
+impl Display for ChaosFault {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChaosFault::RaisePdr { drone, pdr } => {
+                write!(f, "raise PDR of drone #{drone} to {pdr:.2}")
+            }
+            ChaosFault::RemoveLink(a, b) => write!(f, "remove link between #{a} and #{b}"),
+            ChaosFault::Crash(id) => write!(f, "crash drone #{id}"),
+            ChaosFault::AddLink(a, b) => write!(f, "add link between #{a} and #{b}"),
+        }
+    }
+}
+
+/// Picks a random fault among the enabled ones that can be injected in the
+/// current network, or None if there is none. Candidates are sorted, so
+/// that the same seed picks the same faults.
+pub fn pick_fault(
+    data: &SimulationData,
+    config: &ChaosConfig,
+    rng: &mut StdRng,
+) -> Option<ChaosFault> {
+    let topology = data.sc.get_topology();
+    let mut drones = data.sc.get_drone_ids();
+    drones.sort_unstable();
+    let mut nodes: Vec<NodeId> = topology.nodes().collect();
+    nodes.sort_unstable();
+    let mut links: Vec<(NodeId, NodeId)> = topology
+        .all_edges()
+        .map(|(a, b, ())| (a.min(b), a.max(b)))
+        .collect();
+    links.sort_unstable();
+
+    let mut kinds: Vec<Vec<ChaosFault>> = vec![];
+    if config.raise_pdr {
+        kinds.push(
+            drones
+                .iter()
+                .filter_map(|&drone| {
+                    let pdr = data.sc.get_pdr(drone)?;
+                    (pdr < config.max_pdr).then_some(ChaosFault::RaisePdr { drone, pdr })
+                })
+                .collect(),
+        );
+    }
+    if config.remove_link {
+        kinds.push(
+            links
+                .iter()
+                .filter(|&&(a, b)| data.fault_impact(Fault::RemoveLink(a, b)).is_safe())
+                .map(|&(a, b)| ChaosFault::RemoveLink(a, b))
+                .collect(),
+        );
+    }
+    if config.crash {
+        kinds.push(
+            drones
+                .iter()
+                .filter(|&&id| data.fault_impact(Fault::Crash(id)).is_safe())
+                .map(|&id| ChaosFault::Crash(id))
+                .collect(),
+        );
+    }
+    if config.add_link {
+        let mut candidates = vec![];
+        for (i, &a) in nodes.iter().enumerate() {
+            for &b in &nodes[i + 1..] {
+                if !topology.contains_edge(a, b) && can_link(data, a, b) {
+                    candidates.push(ChaosFault::AddLink(a, b));
+                }
+            }
+        }
+        kinds.push(candidates);
+    }
+
+    kinds.retain(|candidates| !candidates.is_empty());
+    if kinds.is_empty() {
+        return None;
+    }
+    let candidates = &kinds[rng.gen_range(0..kinds.len())];
+    let fault = candidates[rng.gen_range(0..candidates.len())];
+    Some(match fault {
+        ChaosFault::RaisePdr { drone, pdr } => {
+            let raised = pdr + rng.gen_range(0.0..=MAX_PDR_RAISE);
+            ChaosFault::RaisePdr {
+                drone,
+                pdr: raised.min(config.max_pdr),
+            }
+        }
+        fault => fault,
+    })
+}
+
+/// Returns true if a link between two nodes would follow the protocol: at
+/// least one of them is a drone, and clients keep at most two links.
+fn can_link(data: &SimulationData, a: NodeId, b: NodeId) -> bool {
+    let topology = data.sc.get_topology();
+    let client_has_room = |id: NodeId| topology.neighbors(id).count() < CLIENT_MAX_LINKS;
+    match (data.node_type(a), data.node_type(b)) {
+        (Some(NodeType::Drone), Some(NodeType::Drone)) => true,
+        (Some(NodeType::Drone), Some(NodeType::Client)) => client_has_room(b),
+        (Some(NodeType::Client), Some(NodeType::Drone)) => client_has_room(a),
+        (Some(NodeType::Drone), Some(NodeType::Server))
+        | (Some(NodeType::Server), Some(NodeType::Drone)) => true,
+        _ => false,
+    }
+}
+
+/// The thread that injects random faults in a running simulation.
+pub struct ChaosThread {
+    handle: JoinHandle<()>,
+    kill_sender: Sender<()>,
+}
+
+impl ChaosThread {
+    /// Spawns a thread that injects a fault every `interval` seconds.
+    pub fn spawn(config: ChaosConfig, data_ref: &Arc<Mutex<SimulationData>>) -> Self {
+        let (kill_sender, kill_recv) = unbounded();
+        let arc_clone = data_ref.clone();
+        let handle = std::thread::spawn(move || {
+            chaos_loop(&config, &arc_clone, &kill_recv);
+        });
+        Self {
+            handle,
+            kill_sender,
+        }
+    }
+
+    /// Stops injecting faults and waits for the thread to terminate.
+    /// # Panics
+    /// Will panic if the thread has panicked.
+    pub fn kill(self) {
+        _ = self.kill_sender.send(());
+        self.handle
+            .join()
+            .expect("Error in joining the chaos thread");
+    }
+}
+
+fn chaos_loop(
+    config: &ChaosConfig,
+    data_ref: &Arc<Mutex<SimulationData>>,
+    rec_kill: &Receiver<()>,
+) {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let interval = Duration::from_secs_f64(config.interval.max(0.1));
+    let start = Instant::now();
+    loop {
+        select_biased! {
+            recv(rec_kill) -> _ => {
+                return;
+            }
+            default(interval) => {}
+        }
+
+        let mut data = data_ref.lock().unwrap();
+        let Some(fault) = pick_fault(&data, config, &mut rng) else {
+            continue;
+        };
+        let applied = fault.apply(&mut data);
+        let entry = if applied {
+            LogEntry::new(
+                fault.node(),
+                LogKind::ControllerAction,
+                format!("Chaos: {fault}"),
+            )
+        } else {
+            LogEntry::new(
+                fault.node(),
+                LogKind::ControllerAction,
+                format!("Chaos: {fault} failed"),
+            )
+            .with_severity(Severity::Error)
+        };
+        data.add_log(entry);
+        data.chaos_reports.push(ChaosReport {
+            at: start.elapsed().as_secs_f64(),
+            fault,
+            applied,
+        });
+        data.request_repaint();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// client 10 is linked to two drones, client 11 and server 20 only to
+    /// drone 1, which no other path replaces. Drone 3 is only linked to
+    /// drone 2.
+    const TOPOLOGY: &str = r"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 10, 11, 20]
+        pdr = 0.0
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 3, 10]
+        pdr = 0.1
+
+        [[drone]]
+        id = 3
+        connected_node_ids = [2]
+        pdr = 0.2
+
+        [[client]]
+        id = 10
+        connected_drone_ids = [1, 2]
+
+        [[client]]
+        id = 11
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 20
+        connected_drone_ids = [1]
+    ";
+
+    /// picks and applies faults to a new simulation of the topology.
+    fn inject(name: &str, config: &ChaosConfig, count: usize) -> Vec<ChaosFault> {
+        let mut data = SimulationData::for_test(name, TOPOLOGY, None);
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        (0..count)
+            .map_while(|_| {
+                let fault = pick_fault(&data, config, &mut rng)?;
+                fault.apply(&mut data);
+                Some(fault)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_faults() {
+        let config = ChaosConfig {
+            seed: 42,
+            ..ChaosConfig::default()
+        };
+        let first = inject("chaos_seed_first", &config, 5);
+        assert!(!first.is_empty());
+        assert_eq!(first, inject("chaos_seed_second", &config, 5));
+    }
+
+    #[test]
+    fn critical_drones_are_never_crashed() {
+        let data = SimulationData::for_test("chaos_critical", TOPOLOGY, None);
+        for seed in 0..20 {
+            let config = ChaosConfig {
+                seed,
+                raise_pdr: false,
+                remove_link: false,
+                crash: true,
+                add_link: false,
+                ..ChaosConfig::default()
+            };
+            let mut rng = StdRng::seed_from_u64(seed);
+            // crashing drone 1 cuts the network, crashing drone 2 isolates
+            // drone 3
+            assert_eq!(
+                pick_fault(&data, &config, &mut rng),
+                Some(ChaosFault::Crash(3))
+            );
+        }
+    }
+
+    #[test]
+    fn links_follow_the_protocol() {
+        let data = SimulationData::for_test("chaos_can_link", TOPOLOGY, None);
+        assert!(can_link(&data, 1, 3));
+        assert!(can_link(&data, 3, 20));
+        assert!(can_link(&data, 11, 3));
+        // client 10 already has two drones
+        assert!(!can_link(&data, 10, 3));
+        assert!(!can_link(&data, 10, 11));
+        assert!(!can_link(&data, 11, 20));
+    }
+}
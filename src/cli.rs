@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
+
+/// Command line arguments of the app.
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "Simulation controller for the Lockheed Rustin drone network"
+)]
+pub struct Args {
+    /// Topology file used to initialize the simulation.
+    #[arg(default_value = "config.toml")]
+    pub topology: PathBuf,
+    /// Drones used when the simulation is initialized.
+    #[arg(short, long, value_enum, default_value_t = DroneMode::Fair)]
+    pub drones: DroneMode,
+    /// Initial width of the window.
+    #[arg(long, default_value_t = 1200.0)]
+    pub width: f32,
+    /// Initial height of the window.
+    #[arg(long, default_value_t = 700.0)]
+    pub height: f32,
+    /// Maximum number of log lines kept for each node, plus as many flood
+    /// packets.
+    #[arg(long, default_value_t = 100)]
+    pub log_capacity: usize,
+    /// Maximum number of events kept in the timeline, and exported, plus as
+    /// many flood packets. Defaults to the log capacity times the number of
+    /// nodes.
+    #[arg(long)]
+    pub timeline_capacity: Option<usize>,
+    /// Scenario to run on the simulation.
+    #[arg(short, long)]
+    pub scenario: Option<PathBuf>,
+    /// Run the simulation without the window and print the statistics at the end.
+    #[arg(long)]
+    pub headless: bool,
+    /// How long the simulation runs in headless mode, in seconds. Defaults to
+    /// the end of the scenario, or to 10 seconds if there is no scenario.
+    #[arg(long, value_parser = parse_seconds)]
+    pub duration: Option<f64>,
+    /// Directory where the events and the statistics are exported at the end
+    /// of a headless simulation.
+    #[arg(long)]
+    pub export: Option<PathBuf>,
+}
+
+/// Parses a number of seconds that can be waited for.
+fn parse_seconds(arg: &str) -> Result<f64, String> {
+    let seconds: f64 = arg.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(seconds)
+        .map(|_| seconds)
+        .map_err(|_| "must be a finite number of seconds, not negative".to_string())
+}
+
+/// Drones that populate the network when the simulation is reset.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DroneMode {
+    /// The drones bought during the fair, randomly assigned.
+    #[default]
+    Fair,
+    /// Our `LockheedRustin` drone for every drone in the topology.
+    LockheedRustin,
+}
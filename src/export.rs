@@ -0,0 +1,294 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+use crate::shared_data::{LogEntry, LogKind, PacketKind, Severity, SimulationData};
+
+/// Name of the file holding the events, one JSON object per line.
+pub const EVENTS_FILE: &str = "events.jsonl";
+/// Name of the file holding the counters of every node.
+pub const STATS_FILE: &str = "stats.csv";
+
+/// Names of the packet types used in the CSV columns, in the order used by
+/// the stats arrays.
+const PACKET_COLUMNS: [&str; 5] = ["fragment", "ack", "nack", "flood_request", "flood_response"];
+
+/// An event as written in the JSON Lines file.
+#[derive(Serialize)]
+struct EventRecord<'a> {
+    /// seconds since the start of the simulation.
+    time: f64,
+    node: NodeId,
+    node_type: Option<&'static str>,
+    kind: &'static str,
+    packet: Option<&'static str>,
+    severity: &'static str,
+    session_id: Option<u64>,
+    /// node that started the session.
+    session_origin: Option<NodeId>,
+    fragment_index: Option<u64>,
+    hops: &'a [NodeId],
+    text: &'a str,
+}
+
+/// The record written before the events when the oldest ones were
+/// discarded from the timeline, so that scripts can tell the export is
+/// incomplete.
+#[derive(Serialize)]
+struct EvictionRecord {
+    kind: &'static str,
+    severity: &'static str,
+    /// number of events missing before the first one.
+    evicted: u64,
+    text: String,
+}
+
+/// Writes the events in the timeline and the counters of every node to
+/// `dir`, creating it if needed.
+/// # Errors
+/// Returns an error if the directory or the files can't be written.
+pub fn export(data: &SimulationData, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    write_events(
+        data,
+        &mut BufWriter::new(File::create(dir.join(EVENTS_FILE))?),
+    )?;
+    write_stats(
+        data,
+        &mut BufWriter::new(File::create(dir.join(STATS_FILE))?),
+    )
+}
+
+/// writes every event of the timeline as a JSON object on its own line,
+/// after a warning if the oldest events were discarded.
+fn write_events(data: &SimulationData, w: &mut impl Write) -> io::Result<()> {
+    let evicted = data.evicted_events();
+    if evicted > 0 {
+        let record = EvictionRecord {
+            kind: "events_evicted",
+            severity: "warning",
+            evicted,
+            text: format!(
+                "the {evicted} oldest events were discarded because the timeline was full"
+            ),
+        };
+        serde_json::to_writer(&mut *w, &record)?;
+        writeln!(w)?;
+    }
+    for entry in data.get_events() {
+        serde_json::to_writer(&mut *w, &event_record(data, entry))?;
+        writeln!(w)?;
+    }
+    w.flush()
+}
+
+/// returns the record of an entry, with names meant to be read by scripts.
+fn event_record<'a>(data: &SimulationData, entry: &'a LogEntry) -> EventRecord<'a> {
+    let (kind, packet) = match entry.kind {
+        LogKind::PacketSent(p) => ("packet_sent", Some(packet_name(p))),
+        LogKind::PacketReceived(p) => ("packet_received", Some(packet_name(p))),
+        LogKind::PacketDropped => ("packet_dropped", Some(packet_name(PacketKind::Fragment))),
+        LogKind::MessageAssembled => ("message_assembled", None),
+        LogKind::MessageFragmented => ("message_fragmented", None),
+        LogKind::ControllerAction => ("controller_action", None),
+        LogKind::Error => ("error", None),
+    };
+    EventRecord {
+        time: entry.time.as_secs_f64(),
+        node: entry.node,
+        node_type: data.node_type(entry.node).map(node_type_name),
+        kind,
+        packet,
+        severity: match entry.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        },
+        session_id: entry.session_id,
+        session_origin: entry.session().map(|(origin, _)| origin),
+        fragment_index: entry.fragment_index,
+        hops: &entry.hops,
+        text: &entry.text,
+    }
+}
+
+/// writes a CSV row for every node, sorted by id. Counters that don't apply
+/// to a node type are left empty.
+fn write_stats(data: &SimulationData, w: &mut impl Write) -> io::Result<()> {
+    let mut header = vec![
+        "node".to_string(),
+        "node_type".to_string(),
+        "name".to_string(),
+    ];
+    for prefix in ["sent", "received", "forwarded"] {
+        header.extend(PACKET_COLUMNS.iter().map(|p| format!("{prefix}_{p}")));
+    }
+    header.extend(
+        [
+            "messages_fragmented",
+            "messages_assembled",
+            "fragments_dropped",
+        ]
+        .map(String::from),
+    );
+    writeln!(w, "{}", header.join(","))?;
+
+    let empty = [None; 5];
+    let mut ids: Vec<NodeId> = data
+        .client_stats
+        .keys()
+        .chain(data.server_stats.keys())
+        .chain(data.drone_stats.keys())
+        .copied()
+        .collect();
+    ids.sort_unstable();
+    for id in ids {
+        let mut row = vec![id.to_string()];
+        if let Some(stats) = data.client_stats.get(&id) {
+            row.extend(["client".to_string(), String::new()]);
+            push_counters(&mut row, &stats.packets_sent.map(Some));
+            push_counters(&mut row, &stats.packets_received.map(Some));
+            push_counters(&mut row, &empty);
+            push_counters(
+                &mut row,
+                &[
+                    Some(stats.messages_fragmented),
+                    Some(stats.messages_assembled),
+                    None,
+                ],
+            );
+        } else if let Some(stats) = data.server_stats.get(&id) {
+            row.extend(["server".to_string(), String::new()]);
+            push_counters(&mut row, &stats.packets_sent.map(Some));
+            push_counters(&mut row, &stats.packets_received.map(Some));
+            push_counters(&mut row, &empty);
+            push_counters(
+                &mut row,
+                &[
+                    Some(stats.messages_fragmented),
+                    Some(stats.messages_assembled),
+                    None,
+                ],
+            );
+        } else if let Some(stats) = data.drone_stats.get(&id) {
+            let name = data
+                .sc
+                .get_group_name(id)
+                .map_or_else(|| "crashed".to_string(), |name| name.to_string());
+            row.extend(["drone".to_string(), csv_field(&name)]);
+            push_counters(&mut row, &empty);
+            push_counters(&mut row, &empty);
+            push_counters(&mut row, &stats.packets_forwarded.map(Some));
+            push_counters(&mut row, &[None, None, Some(stats.fragments_dropped)]);
+        }
+        writeln!(w, "{}", row.join(","))?;
+    }
+    w.flush()
+}
+
+/// appends counters to a CSV row, leaving missing ones empty.
+fn push_counters(row: &mut Vec<String>, counters: &[Option<u64>]) {
+    row.extend(
+        counters
+            .iter()
+            .map(|n| n.map_or(String::new(), |n| n.to_string())),
+    );
+}
+
+/// quotes a CSV field if it contains separators or quotes.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn packet_name(p: PacketKind) -> &'static str {
+    match p {
+        PacketKind::Fragment => PACKET_COLUMNS[0],
+        PacketKind::Ack => PACKET_COLUMNS[1],
+        PacketKind::Nack => PACKET_COLUMNS[2],
+        PacketKind::FloodRequest => PACKET_COLUMNS[3],
+        PacketKind::FloodResponse => PACKET_COLUMNS[4],
+    }
+}
+
+fn node_type_name(t: NodeType) -> &'static str {
+    match t {
+        NodeType::Client => "client",
+        NodeType::Drone => "drone",
+        NodeType::Server => "server",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// client 10 and server 20 linked through drone 1.
+    const TOPOLOGY: &str = r"
+        [[drone]]
+        id = 1
+        connected_node_ids = [10, 20]
+        pdr = 0.0
+
+        [[client]]
+        id = 10
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 20
+        connected_drone_ids = [1]
+    ";
+
+    #[test]
+    fn export_to_a_directory() {
+        let dir = std::env::temp_dir().join("controller_export_test");
+        // the timeline holds two events, so the first one is evicted
+        let mut data = SimulationData::for_test("export", TOPOLOGY, Some(2));
+        for text in ["first", "second", "third"] {
+            data.add_log(LogEntry::new(
+                1,
+                LogKind::ControllerAction,
+                text.to_string(),
+            ));
+        }
+        export(&data, &dir).unwrap();
+        let stats = fs::read_to_string(dir.join(STATS_FILE)).unwrap();
+        let events = fs::read_to_string(dir.join(EVENTS_FILE)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut rows = stats.lines();
+        assert_eq!(
+            rows.next(),
+            Some(
+                "node,node_type,name,\
+                sent_fragment,sent_ack,sent_nack,sent_flood_request,sent_flood_response,\
+                received_fragment,received_ack,received_nack,received_flood_request,received_flood_response,\
+                forwarded_fragment,forwarded_ack,forwarded_nack,forwarded_flood_request,forwarded_flood_response,\
+                messages_fragmented,messages_assembled,fragments_dropped"
+            )
+        );
+        assert_eq!(rows.count(), 3);
+
+        let lines: Vec<serde_json::Value> = events
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["kind"], "events_evicted");
+        assert_eq!(lines[0]["evicted"], 1);
+        assert_eq!(lines[1]["kind"], "controller_action");
+        assert_eq!(lines[1]["node"], 1);
+        assert_eq!(lines[1]["node_type"], "drone");
+        assert_eq!(lines[1]["severity"], "info");
+        assert_eq!(lines[1]["text"], "second");
+        assert_eq!(lines[1]["session_id"], serde_json::Value::Null);
+        assert_eq!(lines[2]["text"], "third");
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::unbounded;
+use wg_2024::network::NodeId;
+
+use crate::cli::Args;
+use crate::export;
+use crate::receiver_threads::ReceiverThreads;
+use crate::scenario::{Scenario, ScenarioError};
+use crate::shared_data::{RepaintNotifier, SimulationData, PACKET_TYPES};
+use crate::topology::{self, TopologyError};
+
+/// Default duration of a headless simulation without a scenario, in seconds.
+const DEFAULT_DURATION: f64 = 10.0;
+
+/// Reasons why a headless simulation fails.
+#[derive(Debug)]
+pub enum HeadlessError {
+    Topology(TopologyError),
+    Scenario(ScenarioError),
+    Export(std::io::Error),
+    /// Some steps of the scenario failed, like an unmet expectation.
+    StepsFailed(usize),
+}
+
+/// Notifier used when there is no ui to repaint.
+pub(crate) struct NoRepaint;
+
+impl RepaintNotifier for NoRepaint {
+    fn request_repaint(&self) {}
+}
+
+/// Runs the simulation without the ui, executing the scenario given in the
+/// arguments if any, then prints the scenario results and the statistics of
+/// every node and exports them if requested.
+/// # Errors
+/// Returns an error if the topology or the scenario can't be used, if the
+/// export fails or if some steps of the scenario failed.
+/// # Panics
+/// Will panic if a receiver thread panics.
+pub fn run(args: &Args) -> Result<(), HeadlessError> {
+    let scenario = match &args.scenario {
+        Some(path) => Scenario::load(path).map_err(HeadlessError::Scenario)?,
+        None => Scenario::default(),
+    };
+    let duration = args.duration.unwrap_or(if args.scenario.is_some() {
+        scenario.duration()
+    } else {
+        DEFAULT_DURATION
+    });
+
+    let sc = topology::load_simulation_controller(&args.topology, args.drones, &BTreeMap::new())
+        .map_err(HeadlessError::Topology)?;
+    let data_ref = Arc::new(Mutex::new(SimulationData::new(
+        sc,
+        args.log_capacity,
+        args.timeline_capacity,
+        Box::new(NoRepaint),
+    )));
+    let threads = ReceiverThreads::spawn(&data_ref);
+
+    // the scenario is never interrupted
+    let start = Instant::now();
+    let (_kill_send, kill_recv) = unbounded();
+    scenario.run(&data_ref, &kill_recv);
+    let remaining = Duration::from_secs_f64(duration).saturating_sub(start.elapsed());
+    thread::sleep(remaining);
+
+    threads.kill();
+    let data = data_ref.lock().unwrap();
+    print_stats(&data);
+    let failed = print_scenario_reports(&data);
+    if let Some(dir) = &args.export {
+        export::export(&data, dir).map_err(HeadlessError::Export)?;
+        println!("Exported to {}", dir.display());
+        if data.evicted_events() > 0 {
+            eprintln!(
+                "Warning: the {} oldest events were discarded from the timeline and are \
+                missing from the export, raise --timeline-capacity to keep them",
+                data.evicted_events()
+            );
+        }
+    }
+    if failed > 0 {
+        return Err(HeadlessError::StepsFailed(failed));
+    }
+    Ok(())
+}
+
+/// prints the result of every executed step, returning how many failed.
+fn print_scenario_reports(data: &SimulationData) -> usize {
+    if data.scenario_reports.is_empty() {
+        return 0;
+    }
+    println!("Scenario");
+    let mut failed = 0;
+    for report in &data.scenario_reports {
+        match &report.result {
+            Ok(()) => println!("  [ok]     {:>7.2}s {}", report.at, report.description),
+            Err(e) => {
+                failed += 1;
+                println!("  [failed] {:>7.2}s {}: {e}", report.at, report.description);
+            }
+        }
+    }
+    failed
+}
+
+/// prints the statistics of every node, sorted by id.
+fn print_stats(data: &SimulationData) {
+    let header = PACKET_TYPES
+        .iter()
+        .map(|t| format!("{t:>12}"))
+        .collect::<String>();
+
+    println!("Clients");
+    for id in sorted_ids(data.client_stats.keys()) {
+        let stats = &data.client_stats[&id];
+        println!("  Client #{id}");
+        println!("    {:<10}{header}", "");
+        println!("    {:<10}{}", "Sent", format_row(&stats.packets_sent));
+        println!(
+            "    {:<10}{}",
+            "Received",
+            format_row(&stats.packets_received)
+        );
+        println!(
+            "    Fragmented messages: {}   Assembled messages: {}",
+            stats.messages_fragmented, stats.messages_assembled
+        );
+    }
+
+    println!("Servers");
+    for id in sorted_ids(data.server_stats.keys()) {
+        let stats = &data.server_stats[&id];
+        println!("  Server #{id}");
+        println!("    {:<10}{header}", "");
+        println!("    {:<10}{}", "Sent", format_row(&stats.packets_sent));
+        println!(
+            "    {:<10}{}",
+            "Received",
+            format_row(&stats.packets_received)
+        );
+        println!(
+            "    Fragmented messages: {}   Assembled messages: {}",
+            stats.messages_fragmented, stats.messages_assembled
+        );
+    }
+
+    println!("Drones");
+    for id in sorted_ids(data.drone_stats.keys()) {
+        let stats = &data.drone_stats[&id];
+        let name = data
+            .sc
+            .get_group_name(id)
+            .map_or_else(|| "crashed".to_string(), |name| name.to_string());
+        println!("  Drone #{id} ({name})");
+        println!("    {:<10}{header}", "");
+        println!(
+            "    {:<10}{}",
+            "Forwarded",
+            format_row(&stats.packets_forwarded)
+        );
+        println!("    Fragments dropped: {}", stats.fragments_dropped);
+    }
+}
+
+/// returns the given ids in ascending order.
+fn sorted_ids<'a>(ids: impl Iterator<Item = &'a NodeId>) -> Vec<NodeId> {
+    let mut v: Vec<NodeId> = ids.copied().collect();
+    v.sort_unstable();
+    v
+}
+
+/// formats a row of packet counters, aligned with the header.
+fn format_row(row: &[u64; 5]) -> String {
+    row.iter().map(|n| format!("{n:>12}")).collect()
+}
+
+impl Display for HeadlessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeadlessError::Topology(e) => write!(f, "{e}"),
+            HeadlessError::Scenario(e) => write!(f, "{e}"),
+            HeadlessError::Export(e) => write!(f, "Cannot export the simulation data: {e}"),
+            HeadlessError::StepsFailed(n) => write!(f, "{n} scenario steps failed"),
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f32::consts::TAU;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use eframe::egui::{pos2, Pos2, Vec2};
+use serde::{Deserialize, Serialize};
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+/// Distance between neighboring nodes, in canvas units.
+const SPACING: f32 = 50.0;
+/// Iterations of the force-directed layout.
+const FORCE_ITERATIONS: usize = 300;
+
+/// Algorithms that place the nodes of the topology. All of them are
+/// deterministic: the same topology always gets the same layout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// spring-electrical layout: links pull nodes together, nodes push each
+    /// other away.
+    #[default]
+    ForceDirected,
+    /// clients on the left, drones in the middle ordered by their distance
+    /// from the clients, servers on the right.
+    Layered,
+    /// all nodes on a circle, grouped by type.
+    Circular,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Layout::ForceDirected, Layout::Layered, Layout::Circular];
+
+    /// Returns the position of every node. Edges between unknown nodes are
+    /// ignored.
+    #[must_use]
+    pub fn compute(
+        self,
+        nodes: &[(NodeId, NodeType)],
+        edges: &[(NodeId, NodeId)],
+    ) -> HashMap<NodeId, Pos2> {
+        let mut nodes = nodes.to_vec();
+        nodes.sort_unstable_by_key(|&(id, node_type)| (type_order(node_type), id));
+        let ids: HashSet<NodeId> = nodes.iter().map(|(id, _)| *id).collect();
+        let mut edges: Vec<(NodeId, NodeId)> = edges
+            .iter()
+            .filter(|(a, b)| a != b && ids.contains(a) && ids.contains(b))
+            .map(|&(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+
+        match self {
+            Layout::ForceDirected => force_directed(&nodes, &edges),
+            Layout::Layered => layered(&nodes, &edges),
+            Layout::Circular => circular(&nodes),
+        }
+    }
+}
+
+/// Node positions saved by the user. They are stored in a file next to the
+/// topology, named after it (`topology.layout.toml` for `topology.toml`),
+/// or in an optional `[layout]` table of the topology itself:
+/// ```toml
+/// [[layout.node]]
+/// id = 1
+/// x = 120.0
+/// y = -40.0
+/// ```
+/// The file next to the topology takes precedence over the table.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SavedLayout {
+    #[serde(default, rename = "node")]
+    pub nodes: Vec<SavedPosition>,
+}
+
+/// Position of a single node in a `SavedLayout`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SavedPosition {
+    pub id: NodeId,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Content of a layout file, or of a topology file with a `[layout]` table.
+/// Every other table of a topology file is ignored.
+#[derive(Serialize, Deserialize, Default)]
+struct LayoutFile {
+    layout: Option<SavedLayout>,
+}
+
+/// Reasons why a saved layout can't be read or written.
+#[derive(Debug)]
+pub enum LayoutFileError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl SavedLayout {
+    /// Returns a layout with the given positions, sorted by node id.
+    #[must_use]
+    pub fn new(positions: impl IntoIterator<Item = (NodeId, Pos2)>) -> Self {
+        let mut nodes: Vec<SavedPosition> = positions
+            .into_iter()
+            .map(|(id, pos)| SavedPosition {
+                id,
+                x: pos.x,
+                y: pos.y,
+            })
+            .collect();
+        nodes.sort_unstable_by_key(|p| p.id);
+        Self { nodes }
+    }
+
+    /// Returns the saved position of every node.
+    #[must_use]
+    pub fn positions(&self) -> HashMap<NodeId, Pos2> {
+        self.nodes.iter().map(|p| (p.id, pos2(p.x, p.y))).collect()
+    }
+
+    /// Returns the path of the layout file of a topology.
+    #[must_use]
+    pub fn path(topology: &Path) -> PathBuf {
+        topology.with_extension("layout.toml")
+    }
+
+    /// Reads the layout saved for a topology, from the layout file if it
+    /// exists or from the `[layout]` table of the topology otherwise.
+    /// # Errors
+    /// Returns an error if one of the files can't be read or parsed.
+    pub fn load(topology: &Path) -> Result<Option<Self>, LayoutFileError> {
+        let path = Self::path(topology);
+        let path = if path.exists() {
+            path
+        } else {
+            topology.to_path_buf()
+        };
+        let file_str =
+            fs::read_to_string(&path).map_err(|e| LayoutFileError::Io(path.clone(), e))?;
+        let file: LayoutFile =
+            toml::from_str(&file_str).map_err(|e| LayoutFileError::Parse(path, e))?;
+        Ok(file.layout)
+    }
+
+    /// Writes the layout to the layout file of a topology, returning its path.
+    /// # Errors
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, topology: &Path) -> Result<PathBuf, LayoutFileError> {
+        let path = Self::path(topology);
+        let file = LayoutFile {
+            layout: Some(self.clone()),
+        };
+        let file_str = toml::to_string(&file).map_err(LayoutFileError::Serialize)?;
+        fs::write(&path, file_str).map_err(|e| LayoutFileError::Io(path.clone(), e))?;
+        Ok(path)
+    }
+
+    /// Deletes the layout file of a topology, if there is one. The `[layout]`
+    /// table of the topology is left untouched.
+    /// # Errors
+    /// Returns an error if the file exists but can't be deleted.
+    pub fn remove(topology: &Path) -> Result<(), LayoutFileError> {
+        let path = Self::path(topology);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(LayoutFileError::Io(path, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// places the nodes on a circle, in the given order.
+fn circular(nodes: &[(NodeId, NodeType)]) -> HashMap<NodeId, Pos2> {
+    let n = nodes.len().max(1) as f32;
+    let radius = (SPACING * n / TAU).max(SPACING);
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| {
+            let angle = TAU * i as f32 / n;
+            (*id, pos2(radius * angle.cos(), radius * angle.sin()))
+        })
+        .collect()
+}
+
+/// Fruchterman-Reingold layout, starting from the circular one.
+fn force_directed(
+    nodes: &[(NodeId, NodeType)],
+    edges: &[(NodeId, NodeId)],
+) -> HashMap<NodeId, Pos2> {
+    let mut positions = circular(nodes);
+    let ids: Vec<NodeId> = nodes.iter().map(|(id, _)| *id).collect();
+    let k = SPACING;
+    let mut temperature = SPACING * (ids.len().max(1) as f32).sqrt();
+    let cooling = temperature / FORCE_ITERATIONS as f32;
+
+    for _ in 0..FORCE_ITERATIONS {
+        let mut displacement: HashMap<NodeId, Vec2> =
+            ids.iter().map(|id| (*id, Vec2::ZERO)).collect();
+        // repulsion between every pair of nodes
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                let delta = positions[a] - positions[b];
+                let distance = delta.length().max(0.01);
+                let force = delta / distance * (k * k / distance);
+                *displacement.get_mut(a).unwrap() += force;
+                *displacement.get_mut(b).unwrap() -= force;
+            }
+        }
+        // attraction along links
+        for (a, b) in edges {
+            let delta = positions[a] - positions[b];
+            let distance = delta.length().max(0.01);
+            let force = delta / distance * (distance * distance / k);
+            *displacement.get_mut(a).unwrap() -= force;
+            *displacement.get_mut(b).unwrap() += force;
+        }
+        for id in &ids {
+            let d = displacement[id];
+            let step = d.length().min(temperature);
+            if step > 0.0 {
+                *positions.get_mut(id).unwrap() += d.normalized() * step;
+            }
+        }
+        temperature = (temperature - cooling).max(0.0);
+    }
+    positions
+}
+
+/// places clients, drones and servers in columns. Drones are split in
+/// columns by their distance from the nearest client, and each column is
+/// ordered by the position of the neighbors in the previous one.
+fn layered(nodes: &[(NodeId, NodeType)], edges: &[(NodeId, NodeId)]) -> HashMap<NodeId, Pos2> {
+    let mut neighbors: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+    for &(a, b) in edges {
+        neighbors.entry(a).or_default().push(b);
+        neighbors.entry(b).or_default().push(a);
+    }
+    let types: HashMap<NodeId, NodeType> = nodes.iter().copied().collect();
+
+    // distance of every drone from the nearest client, through drones
+    let mut distance: HashMap<NodeId, usize> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for (id, node_type) in nodes {
+        if *node_type == NodeType::Client {
+            distance.insert(*id, 0);
+            queue.push_back(*id);
+        }
+    }
+    while let Some(id) = queue.pop_front() {
+        for neighbor in neighbors.get(&id).into_iter().flatten() {
+            if types[neighbor] == NodeType::Drone && !distance.contains_key(neighbor) {
+                distance.insert(*neighbor, distance[&id] + 1);
+                queue.push_back(*neighbor);
+            }
+        }
+    }
+    let drone_columns = nodes
+        .iter()
+        .filter_map(|(id, t)| (*t == NodeType::Drone).then(|| distance.get(id).copied()))
+        .map(|d| d.unwrap_or(1))
+        .max()
+        .unwrap_or(0);
+
+    let mut columns: Vec<Vec<NodeId>> = vec![vec![]; drone_columns + 2];
+    for (id, node_type) in nodes {
+        let column = match node_type {
+            NodeType::Client => 0,
+            // drones that no client can reach go in the first drone column
+            NodeType::Drone => distance.get(id).copied().unwrap_or(1),
+            NodeType::Server => drone_columns + 1,
+        };
+        columns[column].push(*id);
+    }
+
+    let mut positions = HashMap::new();
+    for (c, column) in columns.iter_mut().enumerate() {
+        if c > 0 {
+            // barycenter of the neighbors already placed
+            let barycenter = |id: &NodeId| {
+                let ys: Vec<f32> = neighbors
+                    .get(id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|n| positions.get(n).map(|p: &Pos2| p.y))
+                    .collect();
+                if ys.is_empty() {
+                    f32::MAX
+                } else {
+                    ys.iter().sum::<f32>() / ys.len() as f32
+                }
+            };
+            let mut keyed: Vec<(f32, NodeId)> =
+                column.iter().map(|id| (barycenter(id), *id)).collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            *column = keyed.into_iter().map(|(_, id)| id).collect();
+        }
+        let offset = (column.len() as f32 - 1.0) * SPACING / 2.0;
+        for (row, id) in column.iter().enumerate() {
+            positions.insert(
+                *id,
+                pos2(c as f32 * SPACING * 2.0, row as f32 * SPACING - offset),
+            );
+        }
+    }
+    positions
+}
+
+/// returns the order of the node types in the layouts.
+fn type_order(node_type: NodeType) -> u8 {
+    match node_type {
+        NodeType::Client => 0,
+        NodeType::Drone => 1,
+        NodeType::Server => 2,
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Layout::ForceDirected => write!(f, "Force-directed"),
+            Layout::Layered => write!(f, "Layered"),
+            Layout::Circular => write!(f, "Circular"),
+        }
+    }
+}
+
+impl Display for LayoutFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutFileError::Io(path, e) => {
+                write!(f, "Cannot access the layout file {}: {e}", path.display())
+            }
+            LayoutFileError::Parse(path, e) => {
+                write!(f, "Cannot parse the layout in {}: {e}", path.display())
+            }
+            LayoutFileError::Serialize(e) => write!(f, "Cannot serialize the layout: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// client 10 and server 20 linked through drones 1, 2 and 3.
+    const NODES: [(NodeId, NodeType); 5] = [
+        (1, NodeType::Drone),
+        (2, NodeType::Drone),
+        (3, NodeType::Drone),
+        (10, NodeType::Client),
+        (20, NodeType::Server),
+    ];
+    const EDGES: [(NodeId, NodeId); 5] = [(1, 2), (2, 3), (1, 3), (10, 1), (3, 20)];
+
+    #[test]
+    fn layouts_are_deterministic() {
+        let mut nodes = NODES.to_vec();
+        nodes.reverse();
+        let edges: Vec<(NodeId, NodeId)> = EDGES.iter().map(|&(a, b)| (b, a)).collect();
+        for layout in Layout::ALL {
+            let positions = layout.compute(&NODES, &EDGES);
+            assert_eq!(positions.len(), NODES.len(), "{layout}");
+            // the order of nodes and edges doesn't matter
+            assert_eq!(positions, layout.compute(&nodes, &edges), "{layout}");
+            assert_eq!(positions, layout.compute(&NODES, &EDGES), "{layout}");
+        }
+    }
+
+    #[test]
+    fn layered_layout_columns() {
+        let positions = Layout::Layered.compute(&NODES, &EDGES);
+        for drone in [1, 2, 3] {
+            assert!(positions[&10].x < positions[&drone].x);
+            assert!(positions[&drone].x < positions[&20].x);
+        }
+    }
+
+    #[test]
+    fn saved_layout_round_trip() {
+        let topology = std::env::temp_dir().join("controller_layout_test.toml");
+        fs::write(&topology, "[[layout.node]]\nid = 1\nx = 1.0\ny = 2.0\n").unwrap();
+        let loaded = |topology: &Path| SavedLayout::load(topology).unwrap().unwrap().positions();
+
+        // without a layout file, the table of the topology is used
+        assert_eq!(loaded(&topology), HashMap::from([(1, pos2(1.0, 2.0))]));
+
+        let saved = SavedLayout::new([(2, pos2(5.0, 6.0)), (1, pos2(3.0, 4.0))]);
+        let path = saved.save(&topology).unwrap();
+        assert_eq!(
+            path,
+            std::env::temp_dir().join("controller_layout_test.layout.toml")
+        );
+        assert_eq!(
+            loaded(&topology),
+            HashMap::from([(1, pos2(3.0, 4.0)), (2, pos2(5.0, 6.0))])
+        );
+
+        SavedLayout::remove(&topology).unwrap();
+        assert!(!path.exists());
+        assert_eq!(loaded(&topology), HashMap::from([(1, pos2(1.0, 2.0))]));
+        // removing a missing layout file is not an error
+        SavedLayout::remove(&topology).unwrap();
+        fs::remove_file(&topology).unwrap();
+    }
+}
@@ -1,15 +1,36 @@
 use app::simulation_controller_ui;
+use clap::Parser;
 use eframe::egui;
 
+mod analysis;
 mod app;
+mod bulk;
+mod chaos;
+mod cli;
+mod export;
+mod headless;
+mod layout;
+mod pdr_schedule;
 mod receiver_threads;
+mod scenario;
 pub mod shared_data;
+mod topology;
 mod ui_components;
 
 fn main() -> eframe::Result {
+    let args = cli::Args::parse();
+
+    if args.headless {
+        if let Err(e) = headless::run(&args) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // window options
     let native_options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size((1200.0, 700.0)),
+        viewport: egui::ViewportBuilder::default().with_inner_size((args.width, args.height)),
         ..eframe::NativeOptions::default()
     };
 
@@ -17,9 +38,9 @@ fn main() -> eframe::Result {
     eframe::run_native(
         "Simulation Controller",
         native_options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             Ok(Box::new(
-                simulation_controller_ui::SimulationControllerUI::new(cc),
+                simulation_controller_ui::SimulationControllerUI::new(cc, &args),
             ))
         }),
     )
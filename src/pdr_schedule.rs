@@ -0,0 +1,409 @@
+use std::f64::consts::TAU;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crossbeam_channel::{select_biased, unbounded, Receiver, Sender};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::shared_data::SimulationData;
+
+/// Interval between two updates of the scheduled PDRs.
+pub const TICK: Duration = Duration::from_millis(100);
+
+/// How the PDR of a drone changes over time. Durations and periods are in
+/// seconds, and every value is kept between 0 and 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PdrSchedule {
+    /// goes linearly from `from` to `to`.
+    Ramp { from: f32, to: f32, duration: f64 },
+    /// goes from `from` to `to` in `steps` equal jumps.
+    Steps {
+        from: f32,
+        to: f32,
+        steps: u32,
+        duration: f64,
+    },
+    /// oscillates around `center`.
+    Sine {
+        center: f32,
+        amplitude: f32,
+        period: f64,
+        duration: f64,
+    },
+    /// starts halfway between `min` and `max`, then moves randomly by at
+    /// most `step` at each tick without leaving them.
+    RandomWalk {
+        min: f32,
+        max: f32,
+        step: f32,
+        seed: u64,
+        duration: f64,
+    },
+}
+
+impl Default for PdrSchedule {
+    fn default() -> Self {
+        Self::KINDS[0]
+    }
+}
+
+impl PdrSchedule {
+    /// A schedule of each kind, with default parameters.
+    pub const KINDS: [PdrSchedule; 4] = [
+        PdrSchedule::Ramp {
+            from: 0.0,
+            to: 0.5,
+            duration: 30.0,
+        },
+        PdrSchedule::Steps {
+            from: 0.0,
+            to: 0.5,
+            steps: 5,
+            duration: 30.0,
+        },
+        PdrSchedule::Sine {
+            center: 0.3,
+            amplitude: 0.2,
+            period: 10.0,
+            duration: 60.0,
+        },
+        PdrSchedule::RandomWalk {
+            min: 0.0,
+            max: 0.5,
+            step: 0.02,
+            seed: 0,
+            duration: 60.0,
+        },
+    ];
+
+    /// Returns the name of the kind of schedule.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            PdrSchedule::Ramp { .. } => "Ramp",
+            PdrSchedule::Steps { .. } => "Steps",
+            PdrSchedule::Sine { .. } => "Sine wave",
+            PdrSchedule::RandomWalk { .. } => "Random walk",
+        }
+    }
+
+    /// Returns how long the schedule lasts, in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        match *self {
+            PdrSchedule::Ramp { duration, .. }
+            | PdrSchedule::Steps { duration, .. }
+            | PdrSchedule::Sine { duration, .. }
+            | PdrSchedule::RandomWalk { duration, .. } => duration.max(0.0),
+        }
+    }
+
+    /// Returns the PDR at each tick of the schedule, from its start to its
+    /// end included.
+    #[must_use]
+    pub fn samples(&self) -> Vec<f32> {
+        let tick = TICK.as_secs_f64();
+        let duration = self.duration();
+        let ticks = (duration / tick).ceil() as usize;
+        let mut rng = match *self {
+            PdrSchedule::RandomWalk { seed, .. } => Some(StdRng::seed_from_u64(seed)),
+            _ => None,
+        };
+        let mut walk = None;
+
+        (0..=ticks)
+            .map(|i| {
+                let t = (i as f64 * tick).min(duration);
+                let progress = if duration > 0.0 { t / duration } else { 1.0 };
+                let pdr = match *self {
+                    PdrSchedule::Ramp { from, to, .. } => from + (to - from) * progress as f32,
+                    PdrSchedule::Steps {
+                        from, to, steps, ..
+                    } => {
+                        let steps = f64::from(steps.max(1));
+                        let done = (progress * steps).floor() / steps;
+                        from + (to - from) * done as f32
+                    }
+                    PdrSchedule::Sine {
+                        center,
+                        amplitude,
+                        period,
+                        ..
+                    } => {
+                        if period > 0.0 {
+                            center + amplitude * (TAU * t / period).sin() as f32
+                        } else {
+                            center
+                        }
+                    }
+                    PdrSchedule::RandomWalk { min, max, step, .. } => {
+                        let (low, high) = (min.min(max), min.max(max));
+                        let value = match walk {
+                            None => (low + high) / 2.0,
+                            Some(value) => {
+                                let step = step.abs();
+                                let rng = rng.as_mut().expect("random walks have a generator");
+                                value + rng.gen_range(-step..=step)
+                            }
+                        };
+                        let value = value.clamp(low, high);
+                        walk = Some(value);
+                        value
+                    }
+                };
+                pdr.clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+}
+
+impl Display for PdrSchedule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PdrSchedule::Ramp { from, to, duration } => {
+                write!(f, "ramp from {from} to {to} in {duration} s")
+            }
+            PdrSchedule::Steps {
+                from,
+                to,
+                steps,
+                duration,
+            } => write!(f, "{steps} steps from {from} to {to} in {duration} s"),
+            PdrSchedule::Sine {
+                center,
+                amplitude,
+                period,
+                duration,
+            } => write!(
+                f,
+                "sine wave around {center} ± {amplitude} with period {period} s for {duration} s"
+            ),
+            PdrSchedule::RandomWalk {
+                min,
+                max,
+                step,
+                seed,
+                duration,
+            } => write!(
+                f,
+                "random walk between {min} and {max} by {step} (seed {seed}) for {duration} s"
+            ),
+        }
+    }
+}
+
+/// A schedule being applied to a drone.
+#[derive(Clone, Debug)]
+pub struct ActiveSchedule {
+    pub schedule: PdrSchedule,
+    /// time elapsed since the start of the simulation when the schedule
+    /// started.
+    pub start: Duration,
+    /// last PDR set by the scheduler.
+    pub applied: Option<f32>,
+    samples: Vec<f32>,
+}
+
+impl ActiveSchedule {
+    #[must_use]
+    pub fn new(schedule: PdrSchedule, start: Duration) -> Self {
+        Self {
+            schedule,
+            start,
+            applied: None,
+            samples: schedule.samples(),
+        }
+    }
+
+    /// Returns the PDR at each tick of the schedule.
+    #[must_use]
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// Returns the PDR the drone should have at the given time of the
+    /// simulation, or None if the schedule is over.
+    #[must_use]
+    pub fn pdr_at(&self, now: Duration) -> Option<f32> {
+        let tick = (now.saturating_sub(self.start).as_secs_f64() / TICK.as_secs_f64()) as usize;
+        self.samples.get(tick).copied()
+    }
+}
+
+/// The thread that applies the PDR schedules of a running simulation.
+pub struct PdrScheduler {
+    handle: JoinHandle<()>,
+    kill_sender: Sender<()>,
+}
+
+impl PdrScheduler {
+    /// Spawns a thread that updates the PDR of the scheduled drones at
+    /// every tick.
+    pub fn spawn(data_ref: &Arc<Mutex<SimulationData>>) -> Self {
+        let (kill_sender, kill_recv) = unbounded();
+        let arc_clone = data_ref.clone();
+        let handle = std::thread::spawn(move || {
+            scheduler_loop(&arc_clone, &kill_recv);
+        });
+        Self {
+            handle,
+            kill_sender,
+        }
+    }
+
+    /// Stops the scheduler and waits for the thread to terminate.
+    /// # Panics
+    /// Will panic if the thread has panicked.
+    pub fn kill(self) {
+        _ = self.kill_sender.send(());
+        self.handle
+            .join()
+            .expect("Error in joining the PDR scheduler thread");
+    }
+}
+
+fn scheduler_loop(data_ref: &Arc<Mutex<SimulationData>>, rec_kill: &Receiver<()>) {
+    loop {
+        select_biased! {
+            recv(rec_kill) -> _ => {
+                return;
+            }
+            default(TICK) => {}
+        }
+        data_ref.lock().unwrap().apply_pdr_schedules();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{a} is not {b}");
+    }
+
+    #[test]
+    fn ramp_from_start_to_end() {
+        let samples = PdrSchedule::Ramp {
+            from: 0.0,
+            to: 1.0,
+            duration: 1.0,
+        }
+        .samples();
+        assert_eq!(samples.len(), 11);
+        assert_close(samples[0], 0.0);
+        assert_close(samples[5], 0.5);
+        assert_close(samples[10], 1.0);
+    }
+
+    #[test]
+    fn last_sample_at_the_end() {
+        // the duration is not a multiple of the tick
+        let samples = PdrSchedule::Ramp {
+            from: 0.0,
+            to: 1.0,
+            duration: 0.25,
+        }
+        .samples();
+        assert_eq!(samples.len(), 4);
+        assert_close(samples[3], 1.0);
+    }
+
+    #[test]
+    fn steps_jump_at_equal_intervals() {
+        let samples = PdrSchedule::Steps {
+            from: 0.0,
+            to: 1.0,
+            steps: 2,
+            duration: 1.0,
+        }
+        .samples();
+        assert_close(samples[4], 0.0);
+        assert_close(samples[5], 0.5);
+        assert_close(samples[9], 0.5);
+        assert_close(samples[10], 1.0);
+    }
+
+    #[test]
+    fn sine_around_the_center() {
+        let samples = PdrSchedule::Sine {
+            center: 0.5,
+            amplitude: 0.2,
+            period: 0.4,
+            duration: 1.0,
+        }
+        .samples();
+        assert_close(samples[0], 0.5);
+        assert_close(samples[1], 0.7);
+        assert_close(samples[3], 0.3);
+
+        let flat = PdrSchedule::Sine {
+            center: 0.5,
+            amplitude: 0.2,
+            period: 0.0,
+            duration: 1.0,
+        }
+        .samples();
+        assert!(flat.iter().all(|&pdr| pdr == 0.5));
+    }
+
+    #[test]
+    fn samples_are_valid_pdrs() {
+        let samples = PdrSchedule::Sine {
+            center: 0.9,
+            amplitude: 0.5,
+            period: 1.0,
+            duration: 2.0,
+        }
+        .samples();
+        assert!(samples.iter().all(|pdr| (0.0..=1.0).contains(pdr)));
+        assert!(samples.contains(&1.0));
+    }
+
+    #[test]
+    fn random_walk_is_seeded_and_bounded() {
+        let walk = PdrSchedule::RandomWalk {
+            min: 0.0,
+            max: 0.5,
+            step: 0.1,
+            seed: 42,
+            duration: 10.0,
+        };
+        let samples = walk.samples();
+        assert_eq!(samples, walk.samples());
+        assert_close(samples[0], 0.25);
+        assert!(samples.iter().all(|pdr| (0.0..=0.5).contains(pdr)));
+        assert!(samples
+            .windows(2)
+            .all(|w| (w[1] - w[0]).abs() <= 0.1 + 1e-6));
+    }
+
+    #[test]
+    fn empty_and_negative_durations() {
+        for duration in [0.0, -5.0] {
+            let schedule = PdrSchedule::Ramp {
+                from: 0.2,
+                to: 0.8,
+                duration,
+            };
+            assert_eq!(schedule.duration(), 0.0);
+            assert_eq!(schedule.samples(), vec![0.8]);
+        }
+    }
+
+    #[test]
+    fn active_schedule_ends() {
+        let schedule = PdrSchedule::Ramp {
+            from: 0.0,
+            to: 1.0,
+            duration: 1.0,
+        };
+        let active = ActiveSchedule::new(schedule, Duration::from_secs(10));
+        assert_eq!(active.pdr_at(Duration::from_secs(5)), Some(0.0));
+        assert_eq!(active.pdr_at(Duration::from_secs(11)), Some(1.0));
+        assert_eq!(active.pdr_at(Duration::from_secs(12)), None);
+    }
+}
@@ -3,6 +3,89 @@ mod drone_receiver_thread;
 mod helper;
 mod server_receiver_thread;
 
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crossbeam_channel::{unbounded, Sender};
+
+use crate::shared_data::SimulationData;
+
 pub use client_receiver_thread::receiver_loop as client_receiver_loop;
 pub use drone_receiver_thread::receiver_loop as drone_receiver_loop;
 pub use server_receiver_thread::receiver_loop as server_receiver_loop;
+
+/// The threads that listen for the events sent by the nodes of a simulation
+/// and update its shared data.
+pub struct ReceiverThreads {
+    handles: Vec<JoinHandle<()>>,
+    kill_senders: Vec<Sender<()>>,
+}
+
+impl ReceiverThreads {
+    /// Spawns a receiver thread for each kind of node of the simulation.
+    /// # Panics
+    /// Will panic if the mutex is poisoned.
+    pub fn spawn(data_ref: &Arc<Mutex<SimulationData>>) -> Self {
+        // create channels
+        let (drone_receiver, client_receiver, server_receiver) = {
+            let data = data_ref.lock().unwrap();
+            (
+                data.sc.get_drone_recv(),
+                data.sc.get_client_recv(),
+                data.sc.get_server_recv(),
+            )
+        };
+
+        let (kill_client_send, kill_client_recv) = unbounded();
+        let (kill_server_send, kill_server_recv) = unbounded();
+        let (kill_drone_send, kill_drone_recv) = unbounded();
+        let kill_senders = vec![kill_client_send, kill_server_send, kill_drone_send];
+
+        // spawn receiving threads
+        let mut handles = vec![];
+        let arc_clone = data_ref.clone();
+        let handle = std::thread::spawn(move || {
+            let arc = arc_clone;
+            let event_recv = drone_receiver;
+            let kill_recv = kill_drone_recv;
+            drone_receiver_loop(&arc, &event_recv, &kill_recv);
+        });
+        handles.push(handle);
+
+        let arc_clone = data_ref.clone();
+        let handle = std::thread::spawn(move || {
+            let arc = arc_clone;
+            let event_recv = client_receiver;
+            let kill_recv = kill_client_recv;
+            client_receiver_loop(&arc, &event_recv, &kill_recv);
+        });
+        handles.push(handle);
+
+        let arc_clone = data_ref.clone();
+        let handle = std::thread::spawn(move || {
+            let arc = arc_clone;
+            let event_recv = server_receiver;
+            let kill_recv = kill_server_recv;
+            server_receiver_loop(&arc, &event_recv, &kill_recv);
+        });
+        handles.push(handle);
+
+        Self {
+            handles,
+            kill_senders,
+        }
+    }
+
+    /// Kills the receiver threads and waits for them to terminate.
+    /// # Panics
+    /// Will panic if a thread can't be reached or has panicked.
+    pub fn kill(self) {
+        for s in &self.kill_senders {
+            s.send(())
+                .expect("Error in sending kill message to receiving threads");
+        }
+        for h in self.handles {
+            h.join().expect("Error in joining receiving threads");
+        }
+    }
+}
@@ -1,13 +1,11 @@
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{Arc, Mutex};
 
 use crossbeam_channel::{select_biased, Receiver};
 
 use super::helper;
-use crate::app::simulation_controller_ui::{ContentFile, ContentFileType};
-use crate::shared_data::SimulationData;
+use crate::shared_data::{LogEntry, LogKind, ReceivedFile, SimulationData};
 use drone_network::controller::ClientEvent;
 use drone_network::message::{ClientBody, ServerBody, ServerContentBody};
-use eframe::egui::{Color32, ColorImage, TextureFilter, TextureOptions};
 use wg_2024::network::NodeId;
 use wg_2024::packet::{NodeType, Packet};
 
@@ -68,12 +66,15 @@ fn handle_message_assembled(
     let mut log_line = format!("Assembled message from server #{from}\n");
     log_line.push_str(&helper::get_log_line_server_body(body));
     let mut data = data_ref.lock().unwrap();
-    data.add_log(to, log_line, Color32::WHITE);
+    data.add_log(LogEntry::new(to, LogKind::MessageAssembled, log_line).with_peer(from));
     data.client_stats.get_mut(&to).unwrap().messages_assembled += 1;
     if let ServerBody::ServerContent(ServerContentBody::RespFile(ref v, name)) = body {
-        load_file(&mut data, name, v);
+        data.files.push(ReceivedFile {
+            name: name.to_string(),
+            bytes: v.clone(),
+        });
     }
-    data.ctx.request_repaint();
+    data.request_repaint();
 }
 
 /// update shared data when a message is fragmented
@@ -86,38 +87,10 @@ fn handle_message_fragmented(
     let mut log_line = format!("Fragmented message for server #{to}\n");
     log_line.push_str(&helper::get_log_line_client_body(body));
     let mut data = data_ref.lock().unwrap();
-    data.add_log(from, log_line, Color32::WHITE);
+    data.add_log(LogEntry::new(from, LogKind::MessageFragmented, log_line).with_peer(to));
     data.client_stats
         .get_mut(&from)
         .unwrap()
         .messages_fragmented += 1;
-    data.ctx.request_repaint();
-}
-
-/// load a file assembled by the client and put it in the shared data
-fn load_file(data: &mut MutexGuard<SimulationData>, name: &String, v: &[u8]) {
-    if infer::is_image(v) {
-        let image = image::load_from_memory(v).expect("Failed to load image");
-        let size = [image.width() as usize, image.height() as usize];
-        let rgba = image.to_rgba8();
-        let color_image = ColorImage::from_rgba_unmultiplied(size, &rgba);
-
-        let opt = TextureOptions {
-            magnification: TextureFilter::Nearest,
-            minification: TextureFilter::Nearest,
-            ..TextureOptions::default()
-        };
-
-        let texture = data.ctx.load_texture("my_texture", color_image, opt);
-        data.files.push(ContentFile {
-            name: name.to_string(),
-            file: ContentFileType::Image(texture),
-        });
-    } else {
-        let text = String::from_utf8_lossy(v).to_string();
-        data.files.push(ContentFile {
-            name: name.to_string(),
-            file: ContentFileType::Text(text),
-        });
-    }
+    data.request_repaint();
 }
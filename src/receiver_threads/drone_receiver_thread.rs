@@ -1,12 +1,11 @@
 use std::sync::{Arc, Mutex};
 
 use crossbeam_channel::{select_biased, Receiver};
-use eframe::egui::Color32;
 use wg_2024::controller::DroneEvent;
 use wg_2024::packet::{NodeType, Packet};
 
 use super::helper;
-use crate::shared_data::SimulationData;
+use crate::shared_data::{LogEntry, LogKind, MovementKind, SimulationData};
 
 /// loop that will be running in the thread that listens for `DroneEvents`
 /// and update the shared data accordingly.
@@ -54,18 +53,23 @@ fn handle_packet_dropped(data_ref: &Arc<Mutex<SimulationData>>, p: &Packet) {
 
     // add log
     data.add_log(
-        drone_id,
-        format!("Dropped fragment sent by node #{from_id}"),
-        Color32::LIGHT_RED,
+        LogEntry::new(
+            drone_id,
+            LogKind::PacketDropped,
+            format!("Dropped fragment sent by node #{from_id}"),
+        )
+        .with_packet(p),
     );
 
+    data.add_movement(MovementKind::Dropped { at: drone_id });
+
     // increment stat
     data.drone_stats
         .get_mut(&drone_id)
         .unwrap()
         .fragments_dropped += 1;
 
-    data.ctx.request_repaint();
+    data.request_repaint();
 }
 
 /// update shared data when a packet is sent
@@ -1,9 +1,8 @@
-use crate::shared_data::SimulationData;
+use crate::shared_data::{LogEntry, LogKind, MovementKind, SimulationData};
 use drone_network::message::{
     ClientBody, ClientCommunicationBody, ClientContentBody, ServerBody, ServerCommunicationBody,
     ServerContentBody,
 };
-use eframe::egui::Color32;
 use std::sync::{Arc, Mutex};
 use wg_2024::network::NodeId;
 use wg_2024::packet::{NackType, NodeType, Packet, PacketType};
@@ -16,12 +15,20 @@ pub fn handle_packet_sent(
     data_ref: &Arc<Mutex<SimulationData>>,
 ) {
     let (from_id, to_id) = get_from_and_to_packet_send(p);
-    let log = get_log_packet_sent(p, to_id);
+    let log = get_log_packet_sent(p, from_id, to_id);
     let stat_index = get_packet_stat_index(&p.pack_type);
 
     let mut data = data_ref.lock().unwrap();
-    if let Some((line, color)) = log {
-        data.add_log(from_id, line, color);
+    if let Some(entry) = log {
+        data.add_log(entry);
+    }
+    data.record_traffic(from_id, to_id);
+    if let Some(to) = to_id {
+        data.add_movement(MovementKind::Sent {
+            from: from_id,
+            to,
+            packet: (&p.pack_type).into(),
+        });
     }
     match sender_type {
         NodeType::Client => {
@@ -37,37 +44,30 @@ pub fn handle_packet_sent(
             data.server_stats.get_mut(&from_id).unwrap().packets_sent[stat_index] += 1;
         }
     }
-    data.ctx.request_repaint();
+    data.request_repaint();
 }
 
-/// get log for a packet being sent.
-/// a log is defined by its text and its color.
-fn get_log_packet_sent(p: &Packet, to_id: Option<NodeId>) -> Option<(String, Color32)> {
+/// get log entry for a packet being sent by `from_id`.
+fn get_log_packet_sent(p: &Packet, from_id: NodeId, to_id: Option<NodeId>) -> Option<LogEntry> {
     let line = get_log_line_packet_sent(p, to_id)?;
-    let color = get_log_color_packet(p)?;
-    Some((line, color))
+    let kind = LogKind::PacketSent((&p.pack_type).into());
+    Some(LogEntry::new(from_id, kind, line).with_packet(p))
 }
 
 /// get log text for a packet being sent.
 fn get_log_line_packet_sent(p: &Packet, to_id: Option<NodeId>) -> Option<String> {
-    let to_id = to_id?;
-    match &p.pack_type {
-        PacketType::FloodResponse(_) | PacketType::FloodRequest(_) => None,
-        _ => Some(format!(
+    match (&p.pack_type, to_id) {
+        (PacketType::FloodRequest(fr), _) => Some(format!(
+            "{} #{} sent to neighbors",
+            get_packet_type_str(&p.pack_type),
+            fr.flood_id
+        )),
+        (_, Some(to_id)) => Some(format!(
             "{} sent to node #{}",
             get_packet_type_str(&p.pack_type),
             to_id
         )),
-    }
-}
-
-/// get log color given a packet.
-fn get_log_color_packet(p: &Packet) -> Option<Color32> {
-    match &p.pack_type {
-        PacketType::MsgFragment(_) => Some(Color32::GRAY),
-        PacketType::Ack(_) => Some(Color32::LIGHT_GREEN),
-        PacketType::Nack(_) => Some(Color32::LIGHT_RED),
-        _ => None,
+        (_, None) => None,
     }
 }
 
@@ -101,8 +101,8 @@ pub fn handle_packet_received(
     let stat_index = get_packet_stat_index(&p.pack_type);
 
     let mut data = data_ref.lock().unwrap();
-    if let Some((line, color)) = log {
-        data.add_log(receiver_id, line, color);
+    if let Some(entry) = log {
+        data.add_log(entry);
     }
     match receiver_type {
         NodeType::Client => {
@@ -121,34 +121,28 @@ pub fn handle_packet_received(
             unreachable!()
         }
     }
-    data.ctx.request_repaint();
+    data.request_repaint();
 }
 
-/// get log for a packet being sent.
-/// a log is defined by its text and its color.
-fn get_log_packet_received(p: &Packet, from_id: NodeId) -> Option<(String, Color32)> {
-    let line = get_log_line_packet_received(p, from_id)?;
-    let color = get_log_color_packet(p)?;
-    Some((line, color))
+/// get log entry for a packet received by `receiver_id`.
+fn get_log_packet_received(p: &Packet, receiver_id: NodeId) -> Option<LogEntry> {
+    let line = get_log_line_packet_received(p, receiver_id)?;
+    let kind = LogKind::PacketReceived((&p.pack_type).into());
+    Some(LogEntry::new(receiver_id, kind, line).with_packet(p))
 }
 
 /// get log text for a packet being sent.
 fn get_log_line_packet_received(p: &Packet, receiver_id: NodeId) -> Option<String> {
-    match &p.pack_type {
-        PacketType::FloodResponse(_) | PacketType::FloodRequest(_) => None,
-        _ => {
-            let from_str = if is_shortcut(p, receiver_id) {
-                "SimulationController".to_string()
-            } else {
-                let from_id = get_from_packet_received(p);
-                format!("node #{from_id}")
-            };
-            Some(format!(
-                "Received {} from {from_str}",
-                get_packet_type_str(&p.pack_type),
-            ))
-        }
-    }
+    let from_str = if is_shortcut(p, receiver_id) {
+        "SimulationController".to_string()
+    } else {
+        let from_id = get_from_packet_received(p);
+        format!("node #{from_id}")
+    };
+    Some(format!(
+        "Received {} from {from_str}",
+        get_packet_type_str(&p.pack_type),
+    ))
 }
 
 /// get the `NodeId` of sender of a packet. This assumes that
@@ -3,13 +3,12 @@ use std::sync::{Arc, Mutex};
 use crossbeam_channel::{select_biased, Receiver};
 
 use drone_network::controller::ServerEvent;
-use drone_network::message::{ClientBody, ServerBody};
-use eframe::egui::Color32;
+use drone_network::message::{ClientBody, ServerBody, ServerCommunicationBody, ServerContentBody};
 use wg_2024::network::NodeId;
 use wg_2024::packet::{NodeType, Packet};
 
 use super::helper;
-use crate::shared_data::SimulationData;
+use crate::shared_data::{LogEntry, LogKind, SimulationData};
 
 /// loop that will be running in the thread that listens for `ServerEvents`
 /// and update the shared data accordingly
@@ -68,9 +67,9 @@ fn handle_message_assembled(
     let mut log_line = format!("Assembled message from client #{from}\n");
     log_line.push_str(&helper::get_log_line_client_body(body));
     let mut data = data_ref.lock().unwrap();
-    data.add_log(to, log_line, Color32::WHITE);
+    data.add_log(LogEntry::new(to, LogKind::MessageAssembled, log_line).with_peer(from));
     data.server_stats.get_mut(&to).unwrap().messages_assembled += 1;
-    data.ctx.request_repaint();
+    data.request_repaint();
 }
 
 /// update shared data when a message is fragmented
@@ -83,10 +82,23 @@ fn handle_message_fragmented(
     let mut log_line = format!("Fragmented message for client #{to}\n");
     log_line.push_str(&helper::get_log_line_server_body(body));
     let mut data = data_ref.lock().unwrap();
-    data.add_log(from, log_line, Color32::WHITE);
-    data.server_stats
-        .get_mut(&from)
-        .unwrap()
-        .messages_fragmented += 1;
-    data.ctx.request_repaint();
+    data.add_log(LogEntry::new(from, LogKind::MessageFragmented, log_line).with_peer(to));
+    let stats = data.server_stats.get_mut(&from).unwrap();
+    stats.messages_fragmented += 1;
+    // remember what the server told the client
+    match body {
+        ServerBody::RespServerType(t) => stats.server_type = Some(format!("{t:?}")),
+        ServerBody::ServerContent(ServerContentBody::RespFilesList(files)) => {
+            stats.files = Some(files.iter().map(ToString::to_string).collect());
+        }
+        ServerBody::ServerCommunication(ServerCommunicationBody::RespClientList(clients)) => {
+            // the list is complete, clients that left are not in it
+            stats.chat_clients = clients.iter().copied().collect();
+        }
+        ServerBody::ServerCommunication(ServerCommunicationBody::RegistrationSuccess) => {
+            stats.chat_clients.insert(to);
+        }
+        _ => {}
+    }
+    data.request_repaint();
 }
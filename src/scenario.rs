@@ -0,0 +1,598 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{select_biased, unbounded, Receiver, Sender};
+use drone_network::message::{
+    ClientBody, ClientCommunicationBody, ClientContentBody, CommunicationMessage,
+};
+use serde::Deserialize;
+use wg_2024::network::NodeId;
+
+use crate::shared_data::{HistoryAction, LogEntry, LogKind, Severity, SimulationData};
+
+/// A list of timed actions to perform on a simulation, read from a TOML file:
+/// ```toml
+/// [[step]]
+/// at = 2.0
+/// action = "set_pdr"
+/// drone = 6
+/// pdr = 0.4
+///
+/// [[step]]
+/// at = 10.0
+/// action = "expect"
+/// node = 0
+/// stat = "messages_assembled"
+/// at_least = 1
+/// ```
+#[derive(Deserialize, Debug, Default)]
+pub struct Scenario {
+    #[serde(default, rename = "step")]
+    pub steps: Vec<Step>,
+}
+
+/// An action performed `at` seconds after the start of the scenario.
+#[derive(Deserialize, Debug)]
+pub struct Step {
+    pub at: f64,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// Actions that a scenario can perform. Expectations are checked at the
+/// time of their step.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    SetPdr {
+        drone: NodeId,
+        pdr: f32,
+    },
+    Crash {
+        drone: NodeId,
+    },
+    AddLink {
+        from: NodeId,
+        to: NodeId,
+    },
+    RequestServerType {
+        client: NodeId,
+        server: NodeId,
+    },
+    RequestFilesList {
+        client: NodeId,
+        server: NodeId,
+    },
+    RequestFile {
+        client: NodeId,
+        server: NodeId,
+        file: String,
+    },
+    RegisterToChat {
+        client: NodeId,
+        server: NodeId,
+    },
+    RequestClientList {
+        client: NodeId,
+        server: NodeId,
+    },
+    SendMessage {
+        client: NodeId,
+        server: NodeId,
+        to: NodeId,
+        message: String,
+    },
+    Expect {
+        node: NodeId,
+        stat: Stat,
+        at_least: u64,
+    },
+}
+
+/// Counters that expectations can check.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    MessagesAssembled,
+    MessagesFragmented,
+    FragmentsDropped,
+    FragmentsForwarded,
+    PacketsSent,
+    PacketsReceived,
+}
+
+/// Result of a step that has been executed.
+#[derive(Debug)]
+pub struct StepReport {
+    pub at: f64,
+    pub description: String,
+    pub result: Result<(), String>,
+}
+
+/// Reasons why a scenario file can't be used.
+#[derive(Debug)]
+pub enum ScenarioError {
+    Read(std::io::Error),
+    Parse(toml::de::Error),
+    /// a step time that is negative, not finite or too large.
+    InvalidTime(f64),
+}
+
+impl Scenario {
+    /// Reads the scenario stored at `path`. Steps are sorted by time.
+    /// # Errors
+    /// Returns an error if the file can't be read or parsed, or if a step
+    /// time can't be waited for.
+    pub fn load(path: &Path) -> Result<Self, ScenarioError> {
+        let file_str = fs::read_to_string(path).map_err(ScenarioError::Read)?;
+        Self::parse(&file_str)
+    }
+
+    /// parses and checks a scenario, sorting its steps by time.
+    fn parse(file_str: &str) -> Result<Self, ScenarioError> {
+        let mut scenario: Scenario = toml::from_str(file_str).map_err(ScenarioError::Parse)?;
+        if let Some(step) = scenario
+            .steps
+            .iter()
+            .find(|s| Duration::try_from_secs_f64(s.at).is_err())
+        {
+            return Err(ScenarioError::InvalidTime(step.at));
+        }
+        scenario.steps.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Ok(scenario)
+    }
+
+    /// Time of the last step, in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.steps.last().map_or(0.0, |s| s.at)
+    }
+
+    /// Executes the steps against the simulation at their time, storing a
+    /// report for each of them in the shared data. Returns early if a
+    /// message is received on `rec_kill`.
+    /// # Panics
+    /// Will panic if the mutex is poisoned.
+    pub fn run(&self, data_ref: &Arc<Mutex<SimulationData>>, rec_kill: &Receiver<()>) {
+        let start = Instant::now();
+        for step in &self.steps {
+            let deadline = start + Duration::from_secs_f64(step.at);
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            select_biased! {
+                recv(rec_kill) -> _ => {
+                    return;
+                }
+                default(timeout) => {}
+            }
+
+            let mut data = data_ref.lock().unwrap();
+            let description = step.action.to_string();
+            // a node that isn't in the network has no log to write to
+            let unknown = step.action.unknown_node(|id| {
+                data.drone_stats.contains_key(&id)
+                    || data.client_stats.contains_key(&id)
+                    || data.server_stats.contains_key(&id)
+            });
+            let result = match unknown {
+                Some(id) => Err(format!("node #{id} is not in the network")),
+                None => step.action.execute(&mut data),
+            };
+            if let Some(id) = step.action.node().filter(|_| unknown.is_none()) {
+                let entry = match &result {
+                    Ok(()) => LogEntry::new(
+                        id,
+                        LogKind::ControllerAction,
+                        format!("Scenario: {description}"),
+                    ),
+                    Err(e) => LogEntry::new(
+                        id,
+                        LogKind::ControllerAction,
+                        format!("Scenario: {description} failed: {e}"),
+                    )
+                    .with_severity(Severity::Error),
+                };
+                data.add_log(entry);
+            }
+            data.scenario_reports.push(StepReport {
+                at: step.at,
+                description,
+                result,
+            });
+            data.request_repaint();
+        }
+    }
+}
+
+impl Action {
+    /// performs the action on the simulation. Changes to the network are
+    /// applied like the ones made from the ui, but aren't recorded in the
+    /// history.
+    fn execute(&self, data: &mut SimulationData) -> Result<(), String> {
+        match self {
+            Action::SetPdr { drone, pdr } => data.apply_action(data.pdr_change(*drone, *pdr)),
+            Action::Crash { drone } => data.apply_action(HistoryAction::Crash(*drone)),
+            Action::AddLink { from, to } => data.apply_action(HistoryAction::AddLink(*from, *to)),
+            Action::Expect {
+                node,
+                stat,
+                at_least,
+            } => {
+                let value = stat
+                    .value(data, *node)
+                    .ok_or_else(|| format!("node #{node} has no such statistic"))?;
+                if value >= *at_least {
+                    Ok(())
+                } else {
+                    Err(format!("expected at least {at_least}, got {value}"))
+                }
+            }
+            _ => {
+                let (client, server, body) = self.client_request().unwrap();
+                data.sc
+                    .client_send_message(client, server, body)
+                    .map_err(|e| format!("{e:?}"))
+            }
+        }
+    }
+
+    /// returns the client, the server and the body of the message if the
+    /// action is a client request.
+    fn client_request(&self) -> Option<(NodeId, NodeId, ClientBody)> {
+        let (client, server, body) = match self {
+            Action::RequestServerType { client, server } => {
+                (client, server, ClientBody::ReqServerType)
+            }
+            Action::RequestFilesList { client, server } => (
+                client,
+                server,
+                ClientBody::ClientContent(ClientContentBody::ReqFilesList),
+            ),
+            Action::RequestFile {
+                client,
+                server,
+                file,
+            } => (
+                client,
+                server,
+                ClientBody::ClientContent(ClientContentBody::ReqFile(file.clone())),
+            ),
+            Action::RegisterToChat { client, server } => (
+                client,
+                server,
+                ClientBody::ClientCommunication(ClientCommunicationBody::ReqRegistrationToChat),
+            ),
+            Action::RequestClientList { client, server } => (
+                client,
+                server,
+                ClientBody::ClientCommunication(ClientCommunicationBody::ReqClientList),
+            ),
+            Action::SendMessage {
+                client,
+                server,
+                to,
+                message,
+            } => (
+                client,
+                server,
+                ClientBody::ClientCommunication(ClientCommunicationBody::MessageSend(
+                    CommunicationMessage {
+                        from: *client,
+                        to: *to,
+                        message: message.clone(),
+                    },
+                )),
+            ),
+            _ => return None,
+        };
+        Some((*client, *server, body))
+    }
+
+    /// returns every node the action is about.
+    fn nodes(&self) -> Vec<NodeId> {
+        match self {
+            Action::SetPdr { drone, .. } | Action::Crash { drone } => vec![*drone],
+            Action::AddLink { from, to } => vec![*from, *to],
+            Action::Expect { node, .. } => vec![*node],
+            Action::SendMessage {
+                client, server, to, ..
+            } => vec![*client, *server, *to],
+            _ => self
+                .client_request()
+                .map_or_else(Vec::new, |(client, server, _)| vec![client, server]),
+        }
+    }
+
+    /// returns the first node of the action for which `is_known` is false.
+    fn unknown_node(&self, is_known: impl Fn(NodeId) -> bool) -> Option<NodeId> {
+        self.nodes().into_iter().find(|&id| !is_known(id))
+    }
+
+    /// returns the node whose log should record the action, if any.
+    fn node(&self) -> Option<NodeId> {
+        match self {
+            Action::SetPdr { drone, .. } | Action::Crash { drone } => Some(*drone),
+            Action::AddLink { from, .. } => Some(*from),
+            Action::Expect { .. } => None,
+            _ => self.client_request().map(|(client, _, _)| client),
+        }
+    }
+}
+
+impl Stat {
+    /// returns the value of the statistic for the node, if the node has it.
+    fn value(self, data: &SimulationData, id: NodeId) -> Option<u64> {
+        if let Some(stats) = data.client_stats.get(&id) {
+            return match self {
+                Stat::MessagesAssembled => Some(stats.messages_assembled),
+                Stat::MessagesFragmented => Some(stats.messages_fragmented),
+                Stat::PacketsSent => Some(stats.packets_sent.iter().sum()),
+                Stat::PacketsReceived => Some(stats.packets_received.iter().sum()),
+                Stat::FragmentsDropped | Stat::FragmentsForwarded => None,
+            };
+        }
+        if let Some(stats) = data.server_stats.get(&id) {
+            return match self {
+                Stat::MessagesAssembled => Some(stats.messages_assembled),
+                Stat::MessagesFragmented => Some(stats.messages_fragmented),
+                Stat::PacketsSent => Some(stats.packets_sent.iter().sum()),
+                Stat::PacketsReceived => Some(stats.packets_received.iter().sum()),
+                Stat::FragmentsDropped | Stat::FragmentsForwarded => None,
+            };
+        }
+        let stats = data.drone_stats.get(&id)?;
+        match self {
+            Stat::FragmentsDropped => Some(stats.fragments_dropped),
+            Stat::FragmentsForwarded => Some(stats.packets_forwarded[0]),
+            _ => None,
+        }
+    }
+}
+
+/// The thread that executes a scenario on a running simulation.
+pub struct ScenarioThread {
+    handle: JoinHandle<()>,
+    kill_sender: Sender<()>,
+}
+
+impl ScenarioThread {
+    /// Spawns a thread that runs the scenario on the simulation.
+    pub fn spawn(scenario: Scenario, data_ref: &Arc<Mutex<SimulationData>>) -> Self {
+        let (kill_sender, kill_recv) = unbounded();
+        let arc_clone = data_ref.clone();
+        let handle = std::thread::spawn(move || {
+            scenario.run(&arc_clone, &kill_recv);
+        });
+        Self {
+            handle,
+            kill_sender,
+        }
+    }
+
+    /// Stops the scenario and waits for the thread to terminate.
+    /// # Panics
+    /// Will panic if the thread has panicked.
+    pub fn kill(self) {
+        // the thread may have already finished and dropped the receiver
+        _ = self.kill_sender.send(());
+        self.handle
+            .join()
+            .expect("Error in joining the scenario thread");
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::SetPdr { drone, pdr } => write!(f, "set PDR of drone #{drone} to {pdr}"),
+            Action::Crash { drone } => write!(f, "crash drone #{drone}"),
+            Action::AddLink { from, to } => write!(f, "add link between #{from} and #{to}"),
+            Action::RequestServerType { client, server } => {
+                write!(f, "client #{client} requests the type of server #{server}")
+            }
+            Action::RequestFilesList { client, server } => {
+                write!(
+                    f,
+                    "client #{client} requests the files list from server #{server}"
+                )
+            }
+            Action::RequestFile {
+                client,
+                server,
+                file,
+            } => write!(
+                f,
+                "client #{client} requests file {file} from server #{server}"
+            ),
+            Action::RegisterToChat { client, server } => {
+                write!(
+                    f,
+                    "client #{client} registers to the chat of server #{server}"
+                )
+            }
+            Action::RequestClientList { client, server } => {
+                write!(
+                    f,
+                    "client #{client} requests the client list from server #{server}"
+                )
+            }
+            Action::SendMessage {
+                client, server, to, ..
+            } => write!(
+                f,
+                "client #{client} sends a message to client #{to} through server #{server}"
+            ),
+            Action::Expect {
+                node,
+                stat,
+                at_least,
+            } => write!(f, "expect {stat} of node #{node} to be at least {at_least}"),
+        }
+    }
+}
+
+impl Display for Stat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            Stat::MessagesAssembled => "assembled messages",
+            Stat::MessagesFragmented => "fragmented messages",
+            Stat::FragmentsDropped => "dropped fragments",
+            Stat::FragmentsForwarded => "forwarded fragments",
+            Stat::PacketsSent => "sent packets",
+            Stat::PacketsReceived => "received packets",
+        };
+        write!(f, "{str}")
+    }
+}
+
+impl Display for ScenarioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioError::Read(e) => write!(f, "Cannot read the scenario file: {e}"),
+            ScenarioError::Parse(e) => write!(f, "Cannot parse the scenario file: {e}"),
+            ScenarioError::InvalidTime(at) => write!(
+                f,
+                "Invalid step time {at}: it must be a finite number of seconds, not negative"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn times(scenario: &Scenario) -> Vec<f64> {
+        scenario.steps.iter().map(|s| s.at).collect()
+    }
+
+    #[test]
+    fn valid_scenario() {
+        let scenario = Scenario::parse(
+            r#"
+            [[step]]
+            at = 2.0
+            action = "set_pdr"
+            drone = 6
+            pdr = 0.4
+
+            [[step]]
+            at = 3.5
+            action = "send_message"
+            client = 0
+            server = 2
+            to = 1
+            message = "hello"
+
+            [[step]]
+            at = 10.0
+            action = "expect"
+            node = 0
+            stat = "messages_assembled"
+            at_least = 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(times(&scenario), vec![2.0, 3.5, 10.0]);
+        assert!(matches!(
+            scenario.steps[0].action,
+            Action::SetPdr { drone: 6, pdr } if pdr == 0.4
+        ));
+        assert!(matches!(
+            scenario.steps[2].action,
+            Action::Expect {
+                node: 0,
+                stat: Stat::MessagesAssembled,
+                at_least: 1
+            }
+        ));
+        assert_eq!(scenario.duration(), 10.0);
+    }
+
+    #[test]
+    fn empty_scenario() {
+        let scenario = Scenario::parse("").unwrap();
+        assert!(scenario.steps.is_empty());
+        assert_eq!(scenario.duration(), 0.0);
+    }
+
+    #[test]
+    fn unknown_action() {
+        let result = Scenario::parse(
+            r#"
+            [[step]]
+            at = 1.0
+            action = "explode"
+            drone = 1
+            "#,
+        );
+        assert!(matches!(result, Err(ScenarioError::Parse(_))));
+    }
+
+    #[test]
+    fn invalid_step_times() {
+        for at in ["-1.0", "nan", "inf"] {
+            let result = Scenario::parse(&format!(
+                "[[step]]\nat = {at}\naction = \"crash\"\ndrone = 1\n"
+            ));
+            assert!(
+                matches!(result, Err(ScenarioError::InvalidTime(_))),
+                "at = {at} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn unordered_steps_are_sorted() {
+        let scenario = Scenario::parse(
+            r#"
+            [[step]]
+            at = 5.0
+            action = "crash"
+            drone = 1
+
+            [[step]]
+            at = 0.0
+            action = "crash"
+            drone = 2
+
+            [[step]]
+            at = 2.5
+            action = "crash"
+            drone = 3
+            "#,
+        )
+        .unwrap();
+        assert_eq!(times(&scenario), vec![0.0, 2.5, 5.0]);
+        assert!(matches!(
+            scenario.steps[0].action,
+            Action::Crash { drone: 2 }
+        ));
+        assert_eq!(scenario.duration(), 5.0);
+    }
+
+    #[test]
+    fn unknown_nodes() {
+        let known = |id: NodeId| [0, 1, 2, 6].contains(&id);
+        let message = Action::SendMessage {
+            client: 0,
+            server: 2,
+            to: 9,
+            message: String::new(),
+        };
+        assert_eq!(message.unknown_node(known), Some(9));
+        let link = Action::AddLink { from: 7, to: 6 };
+        assert_eq!(link.unknown_node(known), Some(7));
+        let pdr = Action::SetPdr { drone: 6, pdr: 0.1 };
+        assert_eq!(pdr.unknown_node(known), None);
+        let request = Action::RequestFile {
+            client: 0,
+            server: 3,
+            file: "rust.txt".to_string(),
+        };
+        assert_eq!(request.unknown_node(known), Some(3));
+    }
+}
@@ -1,57 +1,690 @@
-use std::collections::{HashMap, VecDeque};
+mod history;
+mod log_buffer;
+mod log_entry;
+mod sessions;
+mod time_series;
+mod traffic;
 
-use eframe::egui::{Color32, Context};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
-use crate::app::simulation_controller_ui::ContentFile;
+use crate::analysis::{self, Fault, Impact, PathMode};
+use crate::chaos::ChaosReport;
+use crate::pdr_schedule::{ActiveSchedule, PdrSchedule};
+use crate::scenario::StepReport;
+use crate::topology::{self, DroneImplementation, TopologyError};
 use drone_network::controller::SimulationController;
 use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
 
-const MAX_LOG_LENGTH: usize = 100;
+pub use history::{History, HistoryAction, HistoryEntry};
+pub use log_entry::{LogEntry, LogKind, PacketKind, SessionKey, Severity};
+pub use sessions::{Attempt, FragmentTrace, SessionOutcome, SessionTrace};
+pub use time_series::{Series, TimeSeries, SERIES_HISTORY};
+pub use traffic::Traffic;
+
+use log_buffer::LogBuffer;
+use sessions::{ResolvedMessage, SessionTracker};
+
+/// Names of the packet types, in the order used by the stats arrays.
+pub const PACKET_TYPES: [&str; 5] = ["Fragment", "Ack", "Nack", "Flood Req.", "Flood Resp."];
+
+/// Maximum number of packet movements waiting to be animated.
+const MOVEMENTS_CAPACITY: usize = 5000;
+
+/// Something that wants to know when the shared data changes, like the ui
+/// that needs to be repainted.
+pub trait RepaintNotifier: Send {
+    fn request_repaint(&self);
+}
 
 /// Data about the simulation that will be shared between the program's threads
 pub struct SimulationData {
     pub sc: SimulationController,
-    logs: HashMap<NodeId, VecDeque<(String, Color32)>>,
+    start: Instant,
+    logs: HashMap<NodeId, LogBuffer>,
+    log_capacity: usize,
+    /// entries of every node, in chronological order.
+    events: LogBuffer,
+    events_capacity: usize,
+    /// capacity of the timeline asked by the user, if any.
+    timeline_capacity: Option<usize>,
+    /// entries discarded from the timeline because it was full.
+    evicted_events: u64,
+    sessions: SessionTracker,
+    /// events per second of each node and of the whole network.
+    series: HashMap<NodeId, TimeSeries>,
+    global_series: TimeSeries,
+    /// packets that moved through the network since the ui last took them.
+    movements: VecDeque<Movement>,
+    /// packets sent along each link, with the smallest id first.
+    pub edge_traffic: HashMap<(NodeId, NodeId), Traffic>,
+    /// packets sent or forwarded by each node.
+    pub node_traffic: HashMap<NodeId, Traffic>,
     pub drone_stats: HashMap<NodeId, DroneStats>,
     pub client_stats: HashMap<NodeId, ClientStats>,
     pub server_stats: HashMap<NodeId, ServerStats>,
-    pub ctx: Context,
-    pub files: Vec<ContentFile>,
+    notifier: Box<dyn RepaintNotifier>,
+    pub files: Vec<ReceivedFile>,
+    pub scenario_reports: Vec<StepReport>,
+    pub chaos_reports: Vec<ChaosReport>,
+    /// PDR schedules being applied, by drone.
+    pdr_schedules: HashMap<NodeId, ActiveSchedule>,
+    /// actions of the controller that changed the network.
+    history: History,
+}
+
+/// A packet moving through the network, as seen by the receiver threads.
+#[derive(Clone, Copy, Debug)]
+pub struct Movement {
+    /// time elapsed since the start of the simulation.
+    pub time: Duration,
+    pub kind: MovementKind,
+}
+
+/// What happened to a packet.
+#[derive(Clone, Copy, Debug)]
+pub enum MovementKind {
+    /// the packet was sent along the link between two nodes.
+    Sent {
+        from: NodeId,
+        to: NodeId,
+        packet: PacketKind,
+    },
+    /// the fragment was dropped by a drone.
+    Dropped { at: NodeId },
+}
+
+/// A file assembled by a client, waiting to be shown.
+pub struct ReceivedFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
 }
 
 impl SimulationData {
+    /// Returns new shared data with empty logs and stats for every node
+    /// of the simulation. The timeline holds `timeline_capacity` entries,
+    /// or `log_capacity` entries for each node if it is None.
     #[must_use]
     pub fn new(
         sc: SimulationController,
-        logs: HashMap<NodeId, VecDeque<(String, Color32)>>,
-        drone_stats: HashMap<NodeId, DroneStats>,
-        client_stats: HashMap<NodeId, ClientStats>,
-        server_stats: HashMap<NodeId, ServerStats>,
-        ctx: Context,
+        log_capacity: usize,
+        timeline_capacity: Option<usize>,
+        notifier: Box<dyn RepaintNotifier>,
     ) -> Self {
+        let mut logs = HashMap::new();
+        let mut drone_stats = HashMap::new();
+        let mut client_stats = HashMap::new();
+        let mut server_stats = HashMap::new();
+        for id in sc.get_drone_ids() {
+            logs.insert(id, LogBuffer::default());
+            drone_stats.insert(id, DroneStats::default());
+        }
+        for id in sc.get_client_ids() {
+            logs.insert(id, LogBuffer::default());
+            client_stats.insert(id, ClientStats::default());
+        }
+        for id in sc.get_server_ids() {
+            logs.insert(id, LogBuffer::default());
+            server_stats.insert(id, ServerStats::default());
+        }
+        let events_capacity = timeline_capacity.unwrap_or(log_capacity * logs.len());
         Self {
             sc,
+            start: Instant::now(),
             logs,
+            log_capacity,
+            events: LogBuffer::default(),
+            events_capacity,
+            timeline_capacity,
+            evicted_events: 0,
+            sessions: SessionTracker::default(),
+            series: HashMap::new(),
+            global_series: TimeSeries::default(),
+            movements: VecDeque::new(),
+            edge_traffic: HashMap::new(),
+            node_traffic: HashMap::new(),
             drone_stats,
             client_stats,
             server_stats,
-            ctx,
+            notifier,
             files: vec![],
+            scenario_reports: vec![],
+            chaos_reports: vec![],
+            pdr_schedules: HashMap::new(),
+            history: History::default(),
         }
     }
 
-    /// Adds a log entry for the node that matches id.
+    /// Notifies that the data changed.
+    pub fn request_repaint(&self) {
+        self.notifier.request_repaint();
+    }
+
+    /// Adds a log entry for the node it is about and to the timeline,
+    /// timestamped with the time elapsed since the start of the simulation.
+    /// Fragmented and assembled messages are tagged with their session as
+    /// soon as it is known, and the entry is counted in the time series it
+    /// belongs to.
+    ///
+    /// Flood packets and the other entries are kept apart, up to
+    /// `log_capacity` of each for every node and `events_capacity` of each
+    /// in the timeline. When there are more, the oldest entry of the same
+    /// kind is discarded; the ones discarded from the timeline are counted.
     /// # Panics
-    /// Will panic if the id is not present.
-    pub fn add_log(&mut self, id: NodeId, str: String, color: Color32) {
-        let v = self.logs.get_mut(&id).unwrap();
-        if v.len() >= MAX_LOG_LENGTH {
-            v.pop_front();
+    /// Will panic if the node is not present.
+    pub fn add_log(&mut self, mut entry: LogEntry) {
+        entry.time = self.start.elapsed();
+        if let Some(resolved) = self.sessions.track(&mut entry, self.events_capacity) {
+            self.set_message_session(&resolved);
+        }
+        if let Some(series) = Series::of(entry.kind) {
+            let second = entry.time.as_secs();
+            self.global_series.record(second, series);
+            self.series
+                .entry(entry.node)
+                .or_default()
+                .record(second, series);
+        }
+        if self.events.push(entry.clone(), self.events_capacity) {
+            self.evicted_events += 1;
+        }
+        let v = self.logs.get_mut(&entry.node).unwrap();
+        v.push(entry, self.log_capacity);
+    }
+
+    /// sets the session of a fragmented message that was logged before its
+    /// session was known.
+    fn set_message_session(&mut self, resolved: &ResolvedMessage) {
+        let is_message = |e: &&mut LogEntry| {
+            e.kind == LogKind::MessageFragmented
+                && e.node == resolved.node
+                && e.time == resolved.time
+        };
+        if let Some(log) = self.logs.get_mut(&resolved.node) {
+            if let Some(e) = log.others_mut().rev().find(is_message) {
+                e.session_id = Some(resolved.session_id);
+            }
+        }
+        if let Some(e) = self.events.others_mut().rev().find(is_message) {
+            e.session_id = Some(resolved.session_id);
         }
-        v.push_back((str, color));
     }
 
-    /// Clears logs for the node that matches id.
+    /// Spawns a new drone connected to `neighbors` and prepares its logs and
+    /// stats, so that its events can be recorded as soon as it starts.
+    /// # Errors
+    /// Returns an error if the drone would violate the protocol or if the
+    /// controller can't spawn it.
+    pub fn spawn_drone(
+        &mut self,
+        id: NodeId,
+        pdr: f32,
+        neighbors: &[NodeId],
+        implementation: &DroneImplementation,
+    ) -> Result<(), TopologyError> {
+        // crashed drones keep their log and stats, so their ids stay taken
+        let alive = self.sc.get_drone_ids();
+        let crashed: Vec<NodeId> = self
+            .drone_stats
+            .keys()
+            .copied()
+            .filter(|id| !alive.contains(id))
+            .collect();
+        topology::spawn_drone(&mut self.sc, id, pdr, neighbors, implementation, &crashed)?;
+        self.logs.insert(id, LogBuffer::default());
+        self.drone_stats.insert(id, DroneStats::default());
+        if self.timeline_capacity.is_none() {
+            self.events_capacity += self.log_capacity;
+        }
+        let neighbor_list = neighbors
+            .iter()
+            .map(|n| format!("#{n}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.add_log(LogEntry::new(
+            id,
+            LogKind::ControllerAction,
+            format!("Spawned {implementation} drone with PDR {pdr}, linked to {neighbor_list}"),
+        ));
+        for &neighbor in neighbors {
+            self.add_log(LogEntry::new(
+                neighbor,
+                LogKind::ControllerAction,
+                format!("Link added with node {id}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns what the fault would break in the current topology.
+    #[must_use]
+    pub fn fault_impact(&self, fault: Fault) -> Impact {
+        analysis::impact(
+            self.sc.get_topology(),
+            &self.sc.get_client_ids(),
+            &self.sc.get_server_ids(),
+            fault,
+        )
+    }
+
+    /// Returns the drones whose crash, and the links whose removal, would
+    /// break the communication between a client and a server or leave a
+    /// node without links.
+    #[must_use]
+    pub fn unsafe_faults(&self) -> (HashSet<NodeId>, HashSet<(NodeId, NodeId)>) {
+        analysis::unsafe_faults(
+            self.sc.get_topology(),
+            &self.sc.get_client_ids(),
+            &self.sc.get_server_ids(),
+            &self.sc.get_drone_ids(),
+        )
+    }
+
+    /// Returns the paths from a client to a server through drones, shortest
+    /// first, with the probability that a fragment crosses each of them, and
+    /// whether there are more than `limit`.
+    #[must_use]
+    pub fn paths(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        mode: PathMode,
+        limit: usize,
+    ) -> (Vec<(Vec<NodeId>, f64)>, bool) {
+        let (paths, truncated) = analysis::paths(
+            self.sc.get_topology(),
+            &self.sc.get_drone_ids(),
+            from,
+            to,
+            mode,
+            limit,
+        );
+        let paths = paths
+            .into_iter()
+            .map(|path| {
+                let probability = analysis::delivery_probability(&path, |id| self.sc.get_pdr(id));
+                (path, probability)
+            })
+            .collect();
+        (paths, truncated)
+    }
+
+    /// Applies a fault chosen by the user to the simulation, logging the
+    /// result and recording it in the history.
+    /// # Errors
+    /// Returns the error of the controller if the fault can't be applied.
+    pub fn apply_fault(&mut self, fault: Fault) -> Result<(), String> {
+        match fault {
+            Fault::Crash(id) => self.apply_and_record(HistoryAction::Crash(id)),
+            Fault::RemoveLink(a, b) => self.remove_link(a, b),
+        }
+    }
+
+    /// Adds a link between two nodes and logs the result on both of them,
+    /// or the error on the first one.
+    /// # Errors
+    /// Returns the error of the controller if the link can't be added.
+    pub fn add_link(&mut self, id: NodeId, neighbor: NodeId) -> Result<(), String> {
+        self.apply_and_record(HistoryAction::AddLink(id, neighbor))
+    }
+
+    /// Removes the link between two nodes and logs the result on both of
+    /// them, or the error on the first one.
+    /// # Errors
+    /// Returns the error of the controller if the link can't be removed.
+    pub fn remove_link(&mut self, id: NodeId, neighbor: NodeId) -> Result<(), String> {
+        self.apply_and_record(HistoryAction::RemoveLink(id, neighbor))
+    }
+
+    /// Sets the PDR of a drone by hand, stopping its schedule, and logs the
+    /// result.
+    /// # Errors
+    /// Returns the error of the controller if the PDR can't be changed.
+    pub fn set_pdr(&mut self, id: NodeId, pdr: f32) -> Result<(), String> {
+        self.apply_and_record(self.pdr_change(id, pdr))
+    }
+
+    /// Returns the action that sets the PDR of a drone from its current one.
+    #[must_use]
+    pub fn pdr_change(&self, id: NodeId, pdr: f32) -> HistoryAction {
+        HistoryAction::SetPdr {
+            drone: id,
+            from: self.sc.get_pdr(id).unwrap_or_default(),
+            to: pdr,
+        }
+    }
+
+    /// Returns the most recent actions of the user that changed the network.
+    #[must_use]
+    pub fn get_history(&self) -> &History {
+        &self.history
+    }
+
+    /// Returns the crash or link removal that the next undo would apply, if
+    /// any, so that it can be confirmed like the same fault made by hand.
+    #[must_use]
+    pub fn undo_fault(&self) -> Option<Fault> {
+        let index = self.history.next_undo()?;
+        self.history.entries()[index].action.inverse()?.fault()
+    }
+
+    /// Returns the crash or link removal that the next redo would apply, if
+    /// any.
+    #[must_use]
+    pub fn redo_fault(&self) -> Option<Fault> {
+        let index = self.history.next_redo()?;
+        self.history.entries()[index].action.fault()
+    }
+
+    /// Reverts the last reversible action that wasn't undone, and logs the
+    /// result. Returns true if an action was reverted. An action that can't
+    /// be reverted anymore is skipped by the next undos.
+    pub fn undo(&mut self) -> bool {
+        let Some(index) = self.history.next_undo() else {
+            return false;
+        };
+        let action = self.history.entries()[index].action;
+        let Some(inverse) = action.inverse() else {
+            return false;
+        };
+        match self.apply_action(inverse) {
+            Ok(()) => {
+                self.history.set_undone(index);
+                self.add_log(LogEntry::new(
+                    action.node(),
+                    LogKind::ControllerAction,
+                    format!("Undo: {action}"),
+                ));
+                true
+            }
+            Err(e) => {
+                self.history.set_stale(index);
+                self.add_log(
+                    LogEntry::new(
+                        action.node(),
+                        LogKind::ControllerAction,
+                        format!("Cannot undo {action}: {e}"),
+                    )
+                    .with_severity(Severity::Error),
+                );
+                false
+            }
+        }
+    }
+
+    /// Applies again the last undone action, and logs the result. Returns
+    /// true if it was applied.
+    pub fn redo(&mut self) -> bool {
+        let Some(index) = self.history.next_redo() else {
+            return false;
+        };
+        let action = self.history.entries()[index].action;
+        match self.apply_action(action) {
+            Ok(()) => {
+                self.history.set_redone();
+                self.add_log(LogEntry::new(
+                    action.node(),
+                    LogKind::ControllerAction,
+                    format!("Redo: {action}"),
+                ));
+                true
+            }
+            Err(e) => {
+                self.history.clear_redo();
+                self.add_log(
+                    LogEntry::new(
+                        action.node(),
+                        LogKind::ControllerAction,
+                        format!("Cannot redo {action}: {e}"),
+                    )
+                    .with_severity(Severity::Error),
+                );
+                false
+            }
+        }
+    }
+
+    /// Applies an action and records it in the history if it succeeded. Like
+    /// the timeline, the history only keeps the most recent actions.
+    fn apply_and_record(&mut self, action: HistoryAction) -> Result<(), String> {
+        self.apply_action(action)?;
+        self.history
+            .record(self.elapsed(), action, self.events_capacity);
+        Ok(())
+    }
+
+    /// Applies an action to the simulation and logs the result, without
+    /// recording it in the history: only the actions of the user can be
+    /// undone, not the ones of the chaos mode, of scenarios and of bulk
+    /// actions.
+    /// # Errors
+    /// Returns the error of the controller if the action can't be applied.
+    pub fn apply_action(&mut self, action: HistoryAction) -> Result<(), String> {
+        match action {
+            HistoryAction::AddLink(id, neighbor) => match self.sc.add_edge(id, neighbor) {
+                Ok(()) => {
+                    self.add_log(LogEntry::new(
+                        neighbor,
+                        LogKind::ControllerAction,
+                        format!("Link added with node {id}"),
+                    ));
+                    self.add_log(LogEntry::new(
+                        id,
+                        LogKind::ControllerAction,
+                        format!("Link added with node {neighbor}"),
+                    ));
+                    Ok(())
+                }
+                Err(e) => {
+                    self.add_log(
+                        LogEntry::new(
+                            id,
+                            LogKind::ControllerAction,
+                            format!("Error in adding link with {neighbor}: {e:?}"),
+                        )
+                        .with_severity(Severity::Error),
+                    );
+                    Err(format!("{e:?}"))
+                }
+            },
+            HistoryAction::RemoveLink(id, neighbor) => match self.sc.remove_edge(id, neighbor) {
+                Ok(()) => {
+                    self.add_log(LogEntry::new(
+                        neighbor,
+                        LogKind::ControllerAction,
+                        format!("Link removed with node {id}"),
+                    ));
+                    self.add_log(LogEntry::new(
+                        id,
+                        LogKind::ControllerAction,
+                        format!("Link removed with node {neighbor}"),
+                    ));
+                    Ok(())
+                }
+                Err(e) => {
+                    self.add_log(
+                        LogEntry::new(
+                            id,
+                            LogKind::ControllerAction,
+                            format!("Error in removing link with {neighbor}: {e:?}"),
+                        )
+                        .with_severity(Severity::Error),
+                    );
+                    Err(format!("{e:?}"))
+                }
+            },
+            HistoryAction::SetPdr { drone, to, .. } => {
+                self.stop_pdr_schedule(drone);
+                let result = self.sc.set_pdr(drone, to).map_err(|e| format!("{e:?}"));
+                let entry = match &result {
+                    Ok(()) => LogEntry::new(
+                        drone,
+                        LogKind::ControllerAction,
+                        format!("Changed PDR to {to}"),
+                    ),
+                    Err(e) => LogEntry::new(
+                        drone,
+                        LogKind::ControllerAction,
+                        format!("Failed to change PDR: {e}"),
+                    )
+                    .with_severity(Severity::Error),
+                };
+                self.add_log(entry);
+                result
+            }
+            HistoryAction::Crash(id) => match self.sc.crash_drone(id) {
+                Ok(()) => Ok(()),
+                Err(e) => {
+                    self.add_log(
+                        LogEntry::new(
+                            id,
+                            LogKind::ControllerAction,
+                            format!("Cannot crash: {e:?}"),
+                        )
+                        .with_severity(Severity::Error),
+                    );
+                    Err(format!("{e:?}"))
+                }
+            },
+        }
+    }
+
+    /// Starts changing the PDR of a drone over time, replacing its current
+    /// schedule if any.
+    pub fn start_pdr_schedule(&mut self, id: NodeId, schedule: PdrSchedule) {
+        let active = ActiveSchedule::new(schedule, self.elapsed());
+        self.pdr_schedules.insert(id, active);
+        self.add_log(LogEntry::new(
+            id,
+            LogKind::ControllerAction,
+            format!("Started PDR schedule: {schedule}"),
+        ));
+    }
+
+    /// Stops the PDR schedule of a drone, leaving its PDR as it is.
+    pub fn stop_pdr_schedule(&mut self, id: NodeId) {
+        if self.pdr_schedules.remove(&id).is_some() {
+            self.add_log(LogEntry::new(
+                id,
+                LogKind::ControllerAction,
+                "Stopped PDR schedule".to_string(),
+            ));
+        }
+    }
+
+    /// Returns the PDR schedule being applied to a drone.
+    #[must_use]
+    pub fn get_pdr_schedule(&self, id: NodeId) -> Option<&ActiveSchedule> {
+        self.pdr_schedules.get(&id)
+    }
+
+    /// Sets the PDR of every scheduled drone to its current value, and
+    /// forgets the schedules that are over or that can't be applied.
+    pub fn apply_pdr_schedules(&mut self) {
+        let now = self.elapsed();
+        let mut changes = vec![];
+        let mut finished = vec![];
+        for (&id, active) in &mut self.pdr_schedules {
+            match active.pdr_at(now) {
+                Some(pdr) if active.applied != Some(pdr) => {
+                    active.applied = Some(pdr);
+                    changes.push((id, pdr));
+                }
+                Some(_) => {}
+                None => finished.push(id),
+            }
+        }
+        if changes.is_empty() && finished.is_empty() {
+            return;
+        }
+
+        for (id, pdr) in changes {
+            if let Err(e) = self.sc.set_pdr(id, pdr) {
+                self.pdr_schedules.remove(&id);
+                self.add_log(
+                    LogEntry::new(
+                        id,
+                        LogKind::ControllerAction,
+                        format!("PDR schedule stopped, cannot change PDR: {e:?}"),
+                    )
+                    .with_severity(Severity::Error),
+                );
+            }
+        }
+        for id in finished {
+            self.pdr_schedules.remove(&id);
+            self.add_log(LogEntry::new(
+                id,
+                LogKind::ControllerAction,
+                "PDR schedule finished".to_string(),
+            ));
+        }
+        self.request_repaint();
+    }
+
+    /// Records a packet movement to be animated. The oldest one is
+    /// discarded if too many are waiting.
+    pub fn add_movement(&mut self, kind: MovementKind) {
+        if self.movements.len() >= MOVEMENTS_CAPACITY {
+            self.movements.pop_front();
+        }
+        self.movements.push_back(Movement {
+            time: self.start.elapsed(),
+            kind,
+        });
+    }
+
+    /// Counts a packet sent by `from`, along the link to `to` if the packet
+    /// has a single receiver.
+    pub fn record_traffic(&mut self, from: NodeId, to: Option<NodeId>) {
+        let time = self.start.elapsed();
+        self.node_traffic.entry(from).or_default().record(time);
+        if let Some(to) = to {
+            self.edge_traffic
+                .entry((from.min(to), from.max(to)))
+                .or_default()
+                .record(time);
+        }
+    }
+
+    /// Removes and returns the packet movements recorded since the last call.
+    pub fn take_movements(&mut self) -> Vec<Movement> {
+        self.movements.drain(..).collect()
+    }
+
+    /// Returns the time elapsed since the start of the simulation.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the second of the simulation that is running.
+    #[must_use]
+    pub fn current_second(&self) -> u64 {
+        self.start.elapsed().as_secs()
+    }
+
+    /// Returns the events per second of the node that matches id, if any
+    /// event was counted for it.
+    #[must_use]
+    pub fn get_series(&self, id: NodeId) -> Option<&TimeSeries> {
+        self.series.get(&id)
+    }
+
+    /// Returns the events per second of the whole network.
+    #[must_use]
+    pub fn get_global_series(&self) -> &TimeSeries {
+        &self.global_series
+    }
+
+    /// Returns the trace of a session, rebuilt from the timeline.
+    #[must_use]
+    pub fn trace_session(&self, session: SessionKey) -> Option<SessionTrace> {
+        SessionTrace::build(session, self.events.entries())
+    }
+
+    /// Clears logs for the node that matches id. The timeline is kept.
     /// # Panics
     /// Will panic if the id is not present.
     pub fn clear_log(&mut self, id: NodeId) {
@@ -59,20 +692,57 @@ impl SimulationData {
         v.clear();
     }
 
-    /// Clears all logs.
+    /// Clears all logs and the timeline.
     pub fn clear_all_logs(&mut self) {
         for v in self.logs.values_mut() {
             v.clear();
         }
+        self.events.clear();
+        self.evicted_events = 0;
     }
 
-    /// Returns immutable borrow of the `VecDeque` containing all logs
-    /// for the node that matches id.
+    /// Returns the logs of the node that matches id, in chronological
+    /// order.
     /// # Panics
     /// Will panic if the id is not present.
     #[must_use]
-    pub fn get_logs(&self, id: NodeId) -> &VecDeque<(String, Color32)> {
-        self.logs.get(&id).unwrap()
+    pub fn get_logs(&self, id: NodeId) -> impl ExactSizeIterator<Item = &LogEntry> {
+        self.logs.get(&id).unwrap().entries()
+    }
+
+    /// Returns the entries of all nodes, in chronological order.
+    #[must_use]
+    pub fn get_events(&self) -> impl ExactSizeIterator<Item = &LogEntry> {
+        self.events.entries()
+    }
+
+    /// Returns how many of the oldest entries were discarded from the
+    /// timeline because it was full, since it was last cleared.
+    #[must_use]
+    pub fn evicted_events(&self) -> u64 {
+        self.evicted_events
+    }
+
+    /// Returns the ids of every node of the simulation, crashed drones
+    /// included.
+    #[must_use]
+    pub fn get_known_ids(&self) -> Vec<NodeId> {
+        self.logs.keys().copied().collect()
+    }
+
+    /// Returns the type of the node that matches id, if it is part of the
+    /// simulation. Crashed drones are still reported as drones.
+    #[must_use]
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        if self.drone_stats.contains_key(&id) {
+            Some(NodeType::Drone)
+        } else if self.client_stats.contains_key(&id) {
+            Some(NodeType::Client)
+        } else if self.server_stats.contains_key(&id) {
+            Some(NodeType::Server)
+        } else {
+            None
+        }
     }
 }
 
@@ -94,7 +764,8 @@ pub struct ClientStats {
     pub messages_fragmented: u64,
 }
 
-/// Statistics about servers. Contained inside `SimulationData`
+/// Statistics about servers, and what they told their clients. Contained
+/// inside `SimulationData`
 #[derive(Default)]
 pub struct ServerStats {
     // 0:Fragment, 1:Ack, 2:Nack, 3:Flood Req, 4:Flood Resp
@@ -102,4 +773,34 @@ pub struct ServerStats {
     pub packets_received: [u64; 5],
     pub messages_assembled: u64,
     pub messages_fragmented: u64,
+    /// type in the last server type response, if any.
+    pub server_type: Option<String>,
+    /// files in the last files list response, if any.
+    pub files: Option<Vec<String>>,
+    /// clients registered to the chat, from the last client list sent by
+    /// the server and the registrations that followed it.
+    pub chat_clients: BTreeSet<NodeId>,
+}
+
+#[cfg(test)]
+impl SimulationData {
+    /// Returns the data of a simulation of the topology, with our drones,
+    /// for the tests. `name` tells apart the topology files of the tests
+    /// running at the same time.
+    pub(crate) fn for_test(name: &str, topology: &str, timeline_capacity: Option<usize>) -> Self {
+        let path = std::env::temp_dir().join(format!("controller_{name}.toml"));
+        std::fs::write(&path, topology).unwrap();
+        let sc = topology::load_simulation_controller(
+            &path,
+            crate::cli::DroneMode::LockheedRustin,
+            &std::collections::BTreeMap::new(),
+        );
+        std::fs::remove_file(&path).unwrap();
+        Self::new(
+            sc.unwrap(),
+            10,
+            timeline_capacity,
+            Box::new(crate::headless::NoRepaint),
+        )
+    }
 }
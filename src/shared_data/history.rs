@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use wg_2024::network::NodeId;
+
+use crate::analysis::Fault;
+
+/// An action of the controller that changed the network.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HistoryAction {
+    AddLink(NodeId, NodeId),
+    RemoveLink(NodeId, NodeId),
+    SetPdr {
+        drone: NodeId,
+        from: f32,
+        to: f32,
+    },
+    /// crashes can't be undone.
+    Crash(NodeId),
+}
+
+/// An action in the history, with when it happened.
+#[derive(Clone, Copy, Debug)]
+pub struct HistoryEntry {
+    /// time elapsed since the start of the simulation.
+    pub time: Duration,
+    pub action: HistoryAction,
+    pub undone: bool,
+    /// true if undoing the action failed, like re-adding a link to a drone
+    /// that crashed in the meantime. Later undos skip it.
+    pub stale: bool,
+}
+
+/// The most recent actions of the user, oldest first, with the ones that
+/// were undone and can be redone.
+#[derive(Default, Debug)]
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+    /// undone entries, the last one is redone first.
+    redo: Vec<usize>,
+}
+
+impl HistoryAction {
+    /// Returns the action that reverts this one, or None if it can't be
+    /// reverted.
+    #[must_use]
+    pub fn inverse(&self) -> Option<HistoryAction> {
+        match *self {
+            HistoryAction::AddLink(a, b) => Some(HistoryAction::RemoveLink(a, b)),
+            HistoryAction::RemoveLink(a, b) => Some(HistoryAction::AddLink(a, b)),
+            HistoryAction::SetPdr { drone, from, to } => Some(HistoryAction::SetPdr {
+                drone,
+                from: to,
+                to: from,
+            }),
+            HistoryAction::Crash(_) => None,
+        }
+    }
+
+    /// Returns the action as a fault, if it is a crash or a link removal
+    /// whose consequences should be confirmed before applying it.
+    #[must_use]
+    pub fn fault(&self) -> Option<Fault> {
+        match *self {
+            HistoryAction::RemoveLink(a, b) => Some(Fault::RemoveLink(a, b)),
+            HistoryAction::Crash(id) => Some(Fault::Crash(id)),
+            HistoryAction::AddLink(..) | HistoryAction::SetPdr { .. } => None,
+        }
+    }
+
+    /// Returns the node whose log records the action.
+    #[must_use]
+    pub fn node(&self) -> NodeId {
+        match *self {
+            HistoryAction::AddLink(id, _)
+            | HistoryAction::RemoveLink(id, _)
+            | HistoryAction::SetPdr { drone: id, .. }
+            | HistoryAction::Crash(id) => id,
+        }
+    }
+}
+
+impl History {
+    /// Adds an action that has just been applied, forgetting the oldest ones
+    /// beyond `capacity`. The undone actions can't be redone anymore.
+    pub fn record(&mut self, time: Duration, action: HistoryAction, capacity: usize) {
+        self.redo.clear();
+        self.entries.push_back(HistoryEntry {
+            time,
+            action,
+            undone: false,
+            stale: false,
+        });
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &VecDeque<HistoryEntry> {
+        &self.entries
+    }
+
+    /// Returns the index of the entry reverted by the next undo: the last
+    /// reversible one that wasn't undone.
+    #[must_use]
+    pub fn next_undo(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .rposition(|e| !e.undone && !e.stale && e.action.inverse().is_some())
+    }
+
+    /// Returns the index of the entry applied again by the next redo.
+    #[must_use]
+    pub fn next_redo(&self) -> Option<usize> {
+        self.redo.last().copied()
+    }
+
+    /// Marks the entry as undone, so that it can be redone.
+    pub fn set_undone(&mut self, index: usize) {
+        self.entries[index].undone = true;
+        self.redo.push(index);
+    }
+
+    /// Marks the last undone entry as applied again.
+    pub fn set_redone(&mut self) {
+        if let Some(index) = self.redo.pop() {
+            self.entries[index].undone = false;
+        }
+    }
+
+    /// Marks the entry as impossible to undo.
+    pub fn set_stale(&mut self, index: usize) {
+        self.entries[index].stale = true;
+    }
+
+    /// Forgets the undone entries, because redoing them failed.
+    pub fn clear_redo(&mut self) {
+        self.redo.clear();
+    }
+}
+
+impl Display for HistoryAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryAction::AddLink(a, b) => write!(f, "add link between #{a} and #{b}"),
+            HistoryAction::RemoveLink(a, b) => write!(f, "remove link between #{a} and #{b}"),
+            HistoryAction::SetPdr { drone, from, to } => {
+                write!(f, "set PDR of drone #{drone} from {from:.2} to {to:.2}")
+            }
+            HistoryAction::Crash(id) => write!(f, "crash drone #{id}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(actions: &[HistoryAction]) -> History {
+        let mut history = History::default();
+        for (i, &action) in actions.iter().enumerate() {
+            history.record(Duration::from_secs(i as u64), action, 10);
+        }
+        history
+    }
+
+    #[test]
+    fn inverse_actions() {
+        assert_eq!(
+            HistoryAction::AddLink(1, 2).inverse(),
+            Some(HistoryAction::RemoveLink(1, 2))
+        );
+        assert_eq!(
+            HistoryAction::SetPdr {
+                drone: 1,
+                from: 0.1,
+                to: 0.4
+            }
+            .inverse(),
+            Some(HistoryAction::SetPdr {
+                drone: 1,
+                from: 0.4,
+                to: 0.1
+            })
+        );
+        assert_eq!(HistoryAction::Crash(1).inverse(), None);
+    }
+
+    #[test]
+    fn undoing_an_added_link_is_a_fault() {
+        let undo = HistoryAction::AddLink(1, 2).inverse().unwrap();
+        assert_eq!(undo.fault(), Some(Fault::RemoveLink(1, 2)));
+        let undo = HistoryAction::RemoveLink(1, 2).inverse().unwrap();
+        assert_eq!(undo.fault(), None);
+    }
+
+    #[test]
+    fn undo_skips_crashes() {
+        let history = history(&[HistoryAction::AddLink(1, 2), HistoryAction::Crash(3)]);
+        assert_eq!(history.next_undo(), Some(0));
+    }
+
+    #[test]
+    fn undo_then_redo() {
+        let mut history = history(&[
+            HistoryAction::AddLink(1, 2),
+            HistoryAction::RemoveLink(3, 4),
+        ]);
+        history.set_undone(1);
+        history.set_undone(0);
+        assert_eq!(history.next_undo(), None);
+        assert_eq!(history.next_redo(), Some(0));
+        history.set_redone();
+        assert!(!history.entries()[0].undone);
+        assert_eq!(history.next_redo(), Some(1));
+        assert_eq!(history.next_undo(), Some(0));
+    }
+
+    #[test]
+    fn undo_after_a_stale_entry() {
+        let mut history = history(&[
+            HistoryAction::AddLink(1, 2),
+            HistoryAction::RemoveLink(1, 3),
+        ]);
+        // re-adding the link failed, because drone 3 crashed in the meantime
+        history.set_stale(1);
+        assert_eq!(history.next_undo(), Some(0));
+        history.set_undone(0);
+        assert_eq!(history.next_undo(), None);
+        assert_eq!(history.next_redo(), Some(0));
+    }
+
+    #[test]
+    fn record_forgets_the_undone_entries() {
+        let mut history = history(&[HistoryAction::AddLink(1, 2)]);
+        history.set_undone(0);
+        history.record(Duration::from_secs(5), HistoryAction::AddLink(3, 4), 10);
+        assert_eq!(history.next_redo(), None);
+        assert_eq!(history.next_undo(), Some(1));
+    }
+
+    #[test]
+    fn record_forgets_the_oldest_entries() {
+        let mut history = history(&[HistoryAction::AddLink(1, 2), HistoryAction::AddLink(1, 3)]);
+        history.record(Duration::from_secs(5), HistoryAction::AddLink(1, 4), 2);
+        let actions: Vec<_> = history.entries().iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            [HistoryAction::AddLink(1, 3), HistoryAction::AddLink(1, 4)]
+        );
+        assert_eq!(history.next_undo(), Some(1));
+
+        history.record(Duration::from_secs(6), HistoryAction::AddLink(1, 5), 0);
+        assert!(history.entries().is_empty());
+    }
+
+    #[test]
+    fn clear_redo_after_a_failed_redo() {
+        let mut history = history(&[HistoryAction::AddLink(1, 2)]);
+        history.set_undone(0);
+        history.clear_redo();
+        assert_eq!(history.next_redo(), None);
+        assert!(history.entries()[0].undone);
+    }
+}
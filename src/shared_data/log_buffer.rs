@@ -0,0 +1,127 @@
+use std::collections::{vec_deque, VecDeque};
+use std::iter::{FusedIterator, Peekable};
+
+use super::log_entry::LogEntry;
+
+/// Log entries in chronological order, where flood packets have their own
+/// budget: floods dominate the traffic, and would otherwise push out the
+/// fragments, acks and nacks that users look for.
+#[derive(Default, Debug)]
+pub struct LogBuffer {
+    /// flood entries, oldest first.
+    floods: VecDeque<LogEntry>,
+    /// every other entry, oldest first.
+    others: VecDeque<LogEntry>,
+}
+
+/// Iterator over the entries of both budgets, merged by time.
+struct Entries<'a> {
+    floods: Peekable<vec_deque::Iter<'a, LogEntry>>,
+    others: Peekable<vec_deque::Iter<'a, LogEntry>>,
+}
+
+impl LogBuffer {
+    /// Adds an entry, discarding the oldest one of the same budget if there
+    /// are more than `capacity` of them: floods and every other entry are
+    /// counted apart. Returns true if an entry was discarded, which is the
+    /// new one if `capacity` is 0.
+    pub fn push(&mut self, entry: LogEntry, capacity: usize) -> bool {
+        let budget = if entry.is_flood() {
+            &mut self.floods
+        } else {
+            &mut self.others
+        };
+        budget.push_back(entry);
+        if budget.len() > capacity {
+            budget.pop_front();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the entries, oldest first.
+    #[must_use]
+    pub fn entries(&self) -> impl ExactSizeIterator<Item = &LogEntry> {
+        Entries {
+            floods: self.floods.iter().peekable(),
+            others: self.others.iter().peekable(),
+        }
+    }
+
+    /// Returns the entries that aren't floods, newest last. Their kind must
+    /// not be changed.
+    pub fn others_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut LogEntry> {
+        self.others.iter_mut()
+    }
+
+    pub fn clear(&mut self) {
+        self.floods.clear();
+        self.others.clear();
+    }
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = &'a LogEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.floods.peek(), self.others.peek()) {
+            (Some(flood), Some(other)) if flood.time < other.time => self.floods.next(),
+            (_, Some(_)) => self.others.next(),
+            (_, None) => self.floods.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.floods.len() + self.others.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Entries<'_> {}
+
+impl FusedIterator for Entries<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::shared_data::{LogKind, PacketKind};
+
+    fn entry(time: u64, kind: LogKind) -> LogEntry {
+        let mut entry = LogEntry::new(1, kind, time.to_string());
+        entry.time = Duration::from_secs(time);
+        entry
+    }
+
+    fn texts(buffer: &LogBuffer) -> Vec<&str> {
+        buffer.entries().map(|e| e.text.as_str()).collect()
+    }
+
+    const FLOOD: LogKind = LogKind::PacketSent(PacketKind::FloodRequest);
+    const FRAGMENT: LogKind = LogKind::PacketSent(PacketKind::Fragment);
+
+    #[test]
+    fn floods_are_evicted_apart() {
+        let mut buffer = LogBuffer::default();
+        assert!(!buffer.push(entry(0, FRAGMENT), 2));
+        assert!(!buffer.push(entry(1, FLOOD), 2));
+        assert!(!buffer.push(entry(2, FLOOD), 2));
+        assert!(!buffer.push(entry(3, FRAGMENT), 2));
+        // the oldest flood goes, the older fragment stays
+        assert!(buffer.push(entry(4, FLOOD), 2));
+        assert_eq!(texts(&buffer), ["0", "2", "3", "4"]);
+        assert!(buffer.push(entry(5, FRAGMENT), 2));
+        assert_eq!(texts(&buffer), ["2", "3", "4", "5"]);
+        assert_eq!(buffer.entries().len(), 4);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_nothing() {
+        let mut buffer = LogBuffer::default();
+        assert!(buffer.push(entry(0, FRAGMENT), 0));
+        assert!(buffer.push(entry(1, FLOOD), 0));
+        assert_eq!(buffer.entries().len(), 0);
+    }
+}
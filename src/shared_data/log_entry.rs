@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use wg_2024::network::NodeId;
+use wg_2024::packet::{Packet, PacketType};
+
+/// A log line about a node, with the information needed to filter,
+/// export and correlate it with other events.
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    /// time elapsed since the start of the simulation.
+    pub time: Duration,
+    pub node: NodeId,
+    pub kind: LogKind,
+    pub severity: Severity,
+    /// session of the packet the entry is about, if any.
+    pub session_id: Option<u64>,
+    /// fragment the packet refers to, for fragments, acks and nacks.
+    pub fragment_index: Option<u64>,
+    /// number of fragments of the message, for fragments.
+    pub fragment_count: Option<u64>,
+    /// other end of a fragmented or assembled message: its destination or
+    /// its sender.
+    pub peer: Option<NodeId>,
+    /// route of the packet the entry is about, empty if there is none.
+    pub hops: Vec<NodeId>,
+    pub text: String,
+}
+
+/// A session, identified by the node that started it and its id: session
+/// ids are only unique among the sessions of the same node.
+pub type SessionKey = (NodeId, u64);
+
+/// What a log entry is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogKind {
+    PacketSent(PacketKind),
+    PacketReceived(PacketKind),
+    PacketDropped,
+    MessageAssembled,
+    MessageFragmented,
+    ControllerAction,
+    Error,
+}
+
+/// Packet types, without their content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketKind {
+    Fragment,
+    Ack,
+    Nack,
+    FloodRequest,
+    FloodResponse,
+}
+
+/// How important a log entry is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogEntry {
+    /// Returns a new entry about the node, with a severity that depends on
+    /// its kind. The time is set when the entry is added to the shared data.
+    #[must_use]
+    pub fn new(node: NodeId, kind: LogKind, text: String) -> Self {
+        let severity = match kind {
+            LogKind::Error => Severity::Error,
+            LogKind::PacketDropped
+            | LogKind::PacketSent(PacketKind::Nack)
+            | LogKind::PacketReceived(PacketKind::Nack) => Severity::Warning,
+            _ => Severity::Info,
+        };
+        Self {
+            time: Duration::ZERO,
+            node,
+            kind,
+            severity,
+            session_id: None,
+            fragment_index: None,
+            fragment_count: None,
+            peer: None,
+            hops: vec![],
+            text,
+        }
+    }
+
+    /// Sets the session, the fragment index and the route of the entry from
+    /// the packet it is about.
+    #[must_use]
+    pub fn with_packet(mut self, p: &Packet) -> Self {
+        self.session_id = Some(p.session_id);
+        self.hops.clone_from(&p.routing_header.hops);
+        self.fragment_index = match &p.pack_type {
+            PacketType::MsgFragment(f) => Some(f.fragment_index),
+            PacketType::Ack(a) => Some(a.fragment_index),
+            PacketType::Nack(n) => Some(n.fragment_index),
+            PacketType::FloodRequest(_) | PacketType::FloodResponse(_) => None,
+        };
+        if let PacketType::MsgFragment(f) = &p.pack_type {
+            self.fragment_count = Some(f.total_n_fragments);
+        }
+        self
+    }
+
+    /// Sets the other end of a fragmented or assembled message.
+    #[must_use]
+    pub fn with_peer(mut self, peer: NodeId) -> Self {
+        self.peer = Some(peer);
+        self
+    }
+
+    /// Returns the session the entry is about, if it is known. Fragments
+    /// travel from the node that started the session, acks and nacks travel
+    /// back to it.
+    #[must_use]
+    pub fn session(&self) -> Option<SessionKey> {
+        let session_id = self.session_id?;
+        let origin = match self.kind {
+            LogKind::MessageFragmented => Some(self.node),
+            LogKind::MessageAssembled => self.peer,
+            LogKind::PacketSent(PacketKind::Fragment)
+            | LogKind::PacketReceived(PacketKind::Fragment)
+            | LogKind::PacketDropped => self.hops.first().copied(),
+            LogKind::PacketSent(PacketKind::Ack | PacketKind::Nack)
+            | LogKind::PacketReceived(PacketKind::Ack | PacketKind::Nack) => {
+                self.hops.last().copied()
+            }
+            _ => None,
+        }?;
+        Some((origin, session_id))
+    }
+
+    /// Returns true if the entry is about a flood request or response.
+    #[must_use]
+    pub fn is_flood(&self) -> bool {
+        matches!(
+            self.kind,
+            LogKind::PacketSent(PacketKind::FloodRequest | PacketKind::FloodResponse)
+                | LogKind::PacketReceived(PacketKind::FloodRequest | PacketKind::FloodResponse)
+        )
+    }
+
+    /// Overrides the severity of the entry.
+    #[must_use]
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+impl From<&PacketType> for PacketKind {
+    fn from(t: &PacketType) -> Self {
+        match t {
+            PacketType::MsgFragment(_) => PacketKind::Fragment,
+            PacketType::Ack(_) => PacketKind::Ack,
+            PacketType::Nack(_) => PacketKind::Nack,
+            PacketType::FloodRequest(_) => PacketKind::FloodRequest,
+            PacketType::FloodResponse(_) => PacketKind::FloodResponse,
+        }
+    }
+}
@@ -0,0 +1,493 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use wg_2024::network::NodeId;
+
+use super::{LogEntry, LogKind, PacketKind, SessionKey};
+
+/// Ties the messages fragmented and assembled by the nodes to the session
+/// of their packets, since the nodes don't report it in their events.
+///
+/// Nodes fragment their messages and start their sessions in the same
+/// order, so the messages fragmented by a node for a destination are
+/// matched, in order, with the sessions it starts towards that destination.
+/// A message is assembled right after its last missing fragment is
+/// received, so an assembled message is matched with the oldest session
+/// from its sender whose fragments all reached the node. Events of
+/// different node types are handled by different threads, so either side
+/// of a match can be seen first.
+///
+/// Like the timeline, the tracker only remembers the most recent sessions,
+/// so that it doesn't grow for as long as the simulation runs.
+#[derive(Default)]
+pub(super) struct SessionTracker {
+    /// time of the messages fragmented by each node for each destination
+    /// that have not been matched with a session yet.
+    pending_messages: HashMap<(NodeId, NodeId), VecDeque<Duration>>,
+    /// sessions started by each node towards each destination that have
+    /// not been matched with a fragmented message yet.
+    unmatched_sessions: HashMap<(NodeId, NodeId), VecDeque<u64>>,
+    /// sessions that have already been seen.
+    known_sessions: HashSet<SessionKey>,
+    /// the same sessions, in the order they were first seen.
+    known_order: VecDeque<SessionKey>,
+    /// fragments received by the destination of each session.
+    receiving: HashMap<SessionKey, HashSet<u64>>,
+    /// sessions whose fragments all reached their destination, by sender
+    /// and destination, that have not been matched with an assembled
+    /// message yet.
+    complete: HashMap<(NodeId, NodeId), VecDeque<u64>>,
+    /// sessions that don't receive fragments anymore.
+    done: HashSet<SessionKey>,
+}
+
+/// A fragmented message stored before its session was known.
+pub(super) struct ResolvedMessage {
+    pub node: NodeId,
+    pub time: Duration,
+    pub session_id: u64,
+}
+
+impl SessionTracker {
+    /// Updates the tracker with a new entry, setting its session if it is a
+    /// message that can be matched right away. Returns the message stored
+    /// earlier that the entry allowed to match, if any. At most `capacity`
+    /// sessions, and unmatched messages for each destination, are
+    /// remembered.
+    pub(super) fn track(
+        &mut self,
+        entry: &mut LogEntry,
+        capacity: usize,
+    ) -> Option<ResolvedMessage> {
+        match entry.kind {
+            LogKind::MessageFragmented => {
+                let pair = (entry.node, entry.peer?);
+                match self
+                    .unmatched_sessions
+                    .get_mut(&pair)
+                    .and_then(VecDeque::pop_front)
+                {
+                    Some(session_id) => entry.session_id = Some(session_id),
+                    None => {
+                        let pending = self.pending_messages.entry(pair).or_default();
+                        if pending.len() >= capacity {
+                            pending.pop_front();
+                        }
+                        pending.push_back(entry.time);
+                    }
+                }
+                None
+            }
+            LogKind::MessageAssembled => {
+                let pair = (entry.peer?, entry.node);
+                let session_id = self.complete.get_mut(&pair)?.pop_front()?;
+                entry.session_id = Some(session_id);
+                None
+            }
+            LogKind::PacketSent(PacketKind::Fragment)
+            | LogKind::PacketReceived(PacketKind::Fragment)
+            | LogKind::PacketDropped => {
+                let key @ (origin, session_id) = entry.session()?;
+                let &destination = entry.hops.last()?;
+                if matches!(entry.kind, LogKind::PacketReceived(_)) && entry.node == destination {
+                    self.receive_fragment(entry, key, destination);
+                }
+                if !self.known_sessions.insert(key) {
+                    return None;
+                }
+                self.known_order.push_back(key);
+                self.forget_oldest(capacity);
+                let pair = (origin, destination);
+                match self
+                    .pending_messages
+                    .get_mut(&pair)
+                    .and_then(VecDeque::pop_front)
+                {
+                    Some(time) => Some(ResolvedMessage {
+                        node: origin,
+                        time,
+                        session_id,
+                    }),
+                    None => {
+                        self.unmatched_sessions
+                            .entry(pair)
+                            .or_default()
+                            .push_back(session_id);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// records a fragment received by the destination of its session, which
+    /// is complete once every fragment was received.
+    fn receive_fragment(&mut self, entry: &LogEntry, key: SessionKey, destination: NodeId) {
+        let (Some(index), Some(count)) = (entry.fragment_index, entry.fragment_count) else {
+            return;
+        };
+        if self.done.contains(&key) {
+            return;
+        }
+        let received = self.receiving.entry(key).or_default();
+        received.insert(index);
+        if received.len() as u64 >= count {
+            self.receiving.remove(&key);
+            self.done.insert(key);
+            self.complete
+                .entry((key.0, destination))
+                .or_default()
+                .push_back(key.1);
+        }
+    }
+
+    /// forgets the sessions seen first, until at most `capacity` are left.
+    fn forget_oldest(&mut self, capacity: usize) {
+        while self.known_order.len() > capacity {
+            let Some(key @ (origin, session_id)) = self.known_order.pop_front() else {
+                break;
+            };
+            self.known_sessions.remove(&key);
+            self.receiving.remove(&key);
+            self.done.remove(&key);
+            for sessions in self
+                .unmatched_sessions
+                .iter_mut()
+                .chain(self.complete.iter_mut())
+                .filter(|((sender, _), _)| *sender == origin)
+                .map(|(_, sessions)| sessions)
+            {
+                sessions.retain(|&s| s != session_id);
+            }
+        }
+    }
+}
+
+/// The path of a message through the network, rebuilt from the events of
+/// its session.
+#[derive(Debug)]
+pub struct SessionTrace {
+    pub session_id: u64,
+    /// node that started the session and fragmented the message.
+    pub origin: NodeId,
+    /// node the fragments were sent to.
+    pub destination: Option<NodeId>,
+    /// log text of the fragmented message, if it is still in the timeline.
+    pub message: Option<String>,
+    pub fragments: BTreeMap<u64, FragmentTrace>,
+    /// time of the first and of the last event of the session.
+    pub start: Duration,
+    pub end: Duration,
+    pub outcome: SessionOutcome,
+}
+
+/// Every transmission of a fragment.
+#[derive(Default, Debug)]
+pub struct FragmentTrace {
+    pub attempts: Vec<Attempt>,
+    pub acked: bool,
+    /// log text of the nacks received by the origin about the fragment.
+    pub nacks: Vec<String>,
+}
+
+/// A single transmission of a fragment from its origin.
+#[derive(Debug)]
+pub struct Attempt {
+    /// route chosen by the origin.
+    pub route: Vec<NodeId>,
+    /// nodes of the route that the fragment actually reached.
+    pub reached: Vec<NodeId>,
+    pub dropped_at: Option<NodeId>,
+    pub delivered: bool,
+}
+
+/// How a session ended.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SessionOutcome {
+    /// the message was assembled by its destination, after the latency.
+    Delivered(Duration),
+    /// the message has not been assembled (yet).
+    NotDelivered,
+}
+
+impl SessionTrace {
+    /// Rebuilds the trace of a session from the events of the timeline.
+    /// Returns `None` if the timeline holds no event of the session.
+    #[must_use]
+    pub fn build<'a>(
+        session: SessionKey,
+        events: impl IntoIterator<Item = &'a LogEntry>,
+    ) -> Option<Self> {
+        let events: Vec<&LogEntry> = events
+            .into_iter()
+            .filter(|e| e.session() == Some(session))
+            .collect();
+        let start = events.first()?.time;
+        let end = events.last()?.time;
+
+        let (origin, session_id) = session;
+        let mut trace = Self {
+            session_id,
+            origin,
+            destination: None,
+            message: None,
+            fragments: BTreeMap::new(),
+            start,
+            end,
+            outcome: SessionOutcome::NotDelivered,
+        };
+        let mut fragmented_at = None;
+        for e in events {
+            match e.kind {
+                LogKind::MessageFragmented => {
+                    trace.message = Some(e.text.clone());
+                    fragmented_at = Some(e.time);
+                }
+                LogKind::MessageAssembled => {
+                    let latency = e.time.saturating_sub(fragmented_at.unwrap_or(start));
+                    trace.outcome = SessionOutcome::Delivered(latency);
+                }
+                LogKind::PacketSent(PacketKind::Fragment)
+                | LogKind::PacketReceived(PacketKind::Fragment)
+                | LogKind::PacketDropped => trace.add_fragment_event(e),
+                LogKind::PacketReceived(PacketKind::Ack) => {
+                    if let Some(index) = e.fragment_index {
+                        trace.fragments.entry(index).or_default().acked = true;
+                    }
+                }
+                LogKind::PacketReceived(PacketKind::Nack) => {
+                    if let Some(index) = e.fragment_index {
+                        let fragment = trace.fragments.entry(index).or_default();
+                        fragment.nacks.push(e.text.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(trace)
+    }
+
+    /// adds an event about a fragment to its last transmission, or starts
+    /// a new one if the fragment was sent by its origin.
+    fn add_fragment_event(&mut self, e: &LogEntry) {
+        let Some(index) = e.fragment_index else {
+            return;
+        };
+        self.destination = self.destination.or(e.hops.last().copied());
+
+        let fragment = self.fragments.entry(index).or_default();
+        let is_new_attempt = matches!(e.kind, LogKind::PacketSent(_)) && e.node == self.origin;
+        if is_new_attempt || fragment.attempts.is_empty() {
+            fragment.attempts.push(Attempt {
+                route: e.hops.clone(),
+                reached: vec![],
+                dropped_at: None,
+                delivered: false,
+            });
+        }
+        let attempt = fragment.attempts.last_mut().unwrap();
+        if !attempt.reached.contains(&e.node) {
+            attempt.reached.push(e.node);
+        }
+        match e.kind {
+            LogKind::PacketDropped => attempt.dropped_at = Some(e.node),
+            LogKind::PacketReceived(_) => attempt.delivered = true,
+            _ => {}
+        }
+    }
+
+    /// Number of fragments sent again by the origin.
+    #[must_use]
+    pub fn retransmissions(&self) -> usize {
+        self.fragments
+            .values()
+            .map(|f| f.attempts.len().saturating_sub(1))
+            .sum()
+    }
+}
+
+impl Attempt {
+    /// Returns the nodes reached by the fragment, in the order of its route.
+    #[must_use]
+    pub fn path(&self) -> Vec<NodeId> {
+        self.route
+            .iter()
+            .filter(|id| self.reached.contains(id))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, Fragment, Packet, PacketType, FRAGMENT_DSIZE};
+
+    use super::*;
+
+    /// the entries tracked so far, with the sessions set like the shared
+    /// data sets them.
+    #[derive(Default)]
+    struct Log {
+        tracker: SessionTracker,
+        entries: Vec<LogEntry>,
+    }
+
+    impl Log {
+        fn add(&mut self, mut entry: LogEntry) {
+            entry.time = Duration::from_millis(self.entries.len() as u64);
+            if let Some(resolved) = self.tracker.track(&mut entry, 100) {
+                let message = self.entries.iter_mut().find(|e| {
+                    e.kind == LogKind::MessageFragmented
+                        && e.node == resolved.node
+                        && e.time == resolved.time
+                });
+                message.unwrap().session_id = Some(resolved.session_id);
+            }
+            self.entries.push(entry);
+        }
+
+        fn fragmented(&mut self, from: NodeId, to: NodeId) {
+            self.add(LogEntry::new(from, LogKind::MessageFragmented, String::new()).with_peer(to));
+        }
+
+        fn assembled(&mut self, from: NodeId, to: NodeId) {
+            self.add(LogEntry::new(to, LogKind::MessageAssembled, String::new()).with_peer(from));
+        }
+
+        fn fragment(
+            &mut self,
+            kind: LogKind,
+            node: NodeId,
+            hops: &[NodeId],
+            session: u64,
+            index: u64,
+            count: u64,
+        ) {
+            let packet = Packet {
+                routing_header: SourceRoutingHeader {
+                    hop_index: 1,
+                    hops: hops.to_vec(),
+                },
+                session_id: session,
+                pack_type: PacketType::MsgFragment(Fragment {
+                    fragment_index: index,
+                    total_n_fragments: count,
+                    length: 0,
+                    data: [0; FRAGMENT_DSIZE],
+                }),
+            };
+            self.add(LogEntry::new(node, kind, String::new()).with_packet(&packet));
+        }
+
+        fn ack(&mut self, node: NodeId, hops: &[NodeId], session: u64, index: u64) {
+            let packet = Packet {
+                routing_header: SourceRoutingHeader {
+                    hop_index: 1,
+                    hops: hops.to_vec(),
+                },
+                session_id: session,
+                pack_type: PacketType::Ack(Ack {
+                    fragment_index: index,
+                }),
+            };
+            let kind = LogKind::PacketReceived(PacketKind::Ack);
+            self.add(LogEntry::new(node, kind, String::new()).with_packet(&packet));
+        }
+
+        /// sessions of the messages logged by the node, in order.
+        fn messages(&self, node: NodeId, kind: LogKind) -> Vec<Option<SessionKey>> {
+            self.entries
+                .iter()
+                .filter(|e| e.node == node && e.kind == kind)
+                .map(LogEntry::session)
+                .collect()
+        }
+    }
+
+    const SENT: LogKind = LogKind::PacketSent(PacketKind::Fragment);
+    const RECEIVED: LogKind = LogKind::PacketReceived(PacketKind::Fragment);
+
+    #[test]
+    fn clients_sharing_a_session_id() {
+        let mut log = Log::default();
+        log.fragmented(1, 10);
+        log.fragmented(2, 10);
+        log.fragment(SENT, 1, &[1, 5, 10], 7, 0, 1);
+        log.fragment(SENT, 2, &[2, 5, 10], 7, 0, 1);
+        log.fragment(RECEIVED, 10, &[2, 5, 10], 7, 0, 1);
+        log.assembled(2, 10);
+        log.fragment(RECEIVED, 10, &[1, 5, 10], 7, 0, 1);
+        log.assembled(1, 10);
+        log.ack(1, &[10, 5, 1], 7, 0);
+
+        assert_eq!(log.messages(1, LogKind::MessageFragmented), [Some((1, 7))]);
+        assert_eq!(log.messages(2, LogKind::MessageFragmented), [Some((2, 7))]);
+        assert_eq!(
+            log.messages(10, LogKind::MessageAssembled),
+            [Some((2, 7)), Some((1, 7))]
+        );
+
+        let first = SessionTrace::build((1, 7), &log.entries).unwrap();
+        assert_eq!(first.origin, 1);
+        assert_eq!(first.destination, Some(10));
+        assert_eq!(first.fragments[&0].attempts.len(), 1);
+        assert_eq!(first.fragments[&0].attempts[0].route, [1, 5, 10]);
+        assert!(first.fragments[&0].acked);
+        assert_eq!(
+            first.outcome,
+            SessionOutcome::Delivered(Duration::from_millis(7))
+        );
+
+        let second = SessionTrace::build((2, 7), &log.entries).unwrap();
+        assert_eq!(second.fragments[&0].attempts[0].route, [2, 5, 10]);
+        assert!(!second.fragments[&0].acked);
+        assert_eq!(
+            second.outcome,
+            SessionOutcome::Delivered(Duration::from_millis(4))
+        );
+
+        assert!(SessionTrace::build((3, 7), &log.entries).is_none());
+    }
+
+    #[test]
+    fn messages_in_flight_at_once() {
+        let mut log = Log::default();
+        log.fragmented(1, 10);
+        log.fragmented(1, 10);
+        log.fragment(SENT, 1, &[1, 5, 10], 3, 0, 2);
+        log.fragment(SENT, 1, &[1, 5, 10], 4, 0, 1);
+        log.fragment(SENT, 1, &[1, 5, 10], 3, 1, 2);
+        log.fragment(RECEIVED, 10, &[1, 5, 10], 3, 0, 2);
+        log.fragment(RECEIVED, 10, &[1, 5, 10], 4, 0, 1);
+        log.assembled(1, 10);
+        log.fragment(RECEIVED, 10, &[1, 5, 10], 3, 1, 2);
+        log.assembled(1, 10);
+
+        assert_eq!(
+            log.messages(1, LogKind::MessageFragmented),
+            [Some((1, 3)), Some((1, 4))]
+        );
+        assert_eq!(
+            log.messages(10, LogKind::MessageAssembled),
+            [Some((1, 4)), Some((1, 3))]
+        );
+
+        let trace = SessionTrace::build((1, 3), &log.entries).unwrap();
+        assert_eq!(trace.fragments.len(), 2);
+        assert_eq!(trace.retransmissions(), 0);
+        assert!(trace.fragments.values().all(|f| f.attempts[0].delivered));
+        assert_eq!(
+            trace.outcome,
+            SessionOutcome::Delivered(Duration::from_millis(9))
+        );
+
+        let trace = SessionTrace::build((1, 4), &log.entries).unwrap();
+        assert_eq!(trace.fragments.len(), 1);
+        assert_eq!(
+            trace.outcome,
+            SessionOutcome::Delivered(Duration::from_millis(6))
+        );
+    }
+}
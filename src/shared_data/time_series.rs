@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use super::{LogKind, PacketKind};
+
+/// Number of seconds kept in a time series.
+pub const SERIES_HISTORY: u64 = 120;
+
+/// Events counted every second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Series {
+    /// fragments sent by clients and servers, or forwarded by drones.
+    Fragments,
+    /// fragments dropped by drones.
+    Drops,
+    /// nacks sent or received.
+    Nacks,
+    MessagesFragmented,
+    MessagesAssembled,
+}
+
+impl Series {
+    pub const ALL: [Series; 5] = [
+        Series::Fragments,
+        Series::Drops,
+        Series::Nacks,
+        Series::MessagesFragmented,
+        Series::MessagesAssembled,
+    ];
+
+    /// Returns the series that counts the event, if any.
+    #[must_use]
+    pub fn of(kind: LogKind) -> Option<Self> {
+        match kind {
+            LogKind::PacketSent(PacketKind::Fragment) => Some(Series::Fragments),
+            LogKind::PacketDropped => Some(Series::Drops),
+            LogKind::PacketSent(PacketKind::Nack) | LogKind::PacketReceived(PacketKind::Nack) => {
+                Some(Series::Nacks)
+            }
+            LogKind::MessageFragmented => Some(Series::MessagesFragmented),
+            LogKind::MessageAssembled => Some(Series::MessagesAssembled),
+            _ => None,
+        }
+    }
+
+    /// Returns a short name for legends.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Series::Fragments => "Fragments",
+            Series::Drops => "Drops",
+            Series::Nacks => "Nacks",
+            Series::MessagesFragmented => "Fragmented msgs",
+            Series::MessagesAssembled => "Assembled msgs",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Counts of events in each second of the last `SERIES_HISTORY` seconds.
+#[derive(Default, Debug)]
+pub struct TimeSeries {
+    /// second of the simulation counted by the first bucket.
+    first_second: u64,
+    buckets: VecDeque<[u64; Series::ALL.len()]>,
+}
+
+impl TimeSeries {
+    /// Counts an event that happened during the given second.
+    pub fn record(&mut self, second: u64, series: Series) {
+        self.advance(second);
+        if let Some(bucket) = self.buckets.back_mut() {
+            bucket[series.index()] += 1;
+        }
+    }
+
+    /// adds empty buckets up to `second`, discarding the ones that are too old.
+    fn advance(&mut self, second: u64) {
+        if self.buckets.is_empty() {
+            self.first_second = second;
+        }
+        while self.first_second + (self.buckets.len() as u64) <= second {
+            self.buckets.push_back([0; Series::ALL.len()]);
+        }
+        while self.buckets.len() as u64 > SERIES_HISTORY {
+            self.buckets.pop_front();
+            self.first_second += 1;
+        }
+    }
+
+    /// Returns a point for each of the last `SERIES_HISTORY` seconds up to
+    /// `now`, with the second and the count. Seconds without events count
+    /// zero.
+    #[must_use]
+    pub fn points(&self, series: Series, now: u64) -> Vec<[f64; 2]> {
+        let from = (now + 1).saturating_sub(SERIES_HISTORY);
+        (from..=now)
+            .map(|second| {
+                let count = second
+                    .checked_sub(self.first_second)
+                    .and_then(|i| self.buckets.get(usize::try_from(i).ok()?))
+                    .map_or(0, |bucket| bucket[series.index()]);
+                [second as f64, count as f64]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// Time constant of the decay of the recent traffic, in seconds. Packets
+/// older than a few time constants barely count.
+const DECAY_TIME: f64 = 2.0;
+
+/// Packets that went through a link or a node, both in total and recently.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Traffic {
+    pub total: u64,
+    /// recent packets per second at the time of the last packet.
+    rate: f64,
+    last: Duration,
+}
+
+impl Traffic {
+    /// Counts a packet seen at `time`.
+    pub fn record(&mut self, time: Duration) {
+        self.rate = self.rate(time) + 1.0 / DECAY_TIME;
+        self.last = time;
+        self.total += 1;
+    }
+
+    /// Returns the packets per second seen recently, with an exponential
+    /// decay so that a link that stops carrying traffic cools down.
+    #[must_use]
+    pub fn rate(&self, now: Duration) -> f64 {
+        let age = now.saturating_sub(self.last).as_secs_f64();
+        self.rate * (-age / DECAY_TIME).exp()
+    }
+}
@@ -0,0 +1,771 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use drone_network::controller::SimulationController;
+use drone_network::network::{
+    drone_factory, fair_drone_factory, fair_drone_names, init_network, init_network_with_drone,
+    init_network_with_factories, DroneFactory,
+};
+use lockheedrustin_drone::LockheedRustin;
+use serde::Deserialize;
+use wg_2024::config::Config;
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+use crate::cli::DroneMode;
+
+/// Reasons why a topology file can't be used to start a simulation.
+#[derive(Debug)]
+pub enum TopologyError {
+    Read(std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(Vec<TopologyIssue>),
+    Init(String),
+    Spawn(String),
+}
+
+/// A single violation of the protocol found in a topology.
+#[derive(Debug, PartialEq)]
+pub enum TopologyIssue {
+    DuplicateId(NodeId),
+    UnknownNeighbor {
+        id: NodeId,
+        neighbor: NodeId,
+    },
+    SelfLink(NodeId),
+    AsymmetricLink {
+        id: NodeId,
+        neighbor: NodeId,
+    },
+    ClientDroneCount {
+        id: NodeId,
+        count: usize,
+    },
+    ServerDroneCount {
+        id: NodeId,
+        count: usize,
+    },
+    ForbiddenLink {
+        id: NodeId,
+        neighbor: NodeId,
+    },
+    PdrOutOfRange {
+        id: NodeId,
+        pdr: f32,
+    },
+    Unreachable {
+        client: NodeId,
+        server: NodeId,
+    },
+    UnknownImplementation {
+        id: NodeId,
+        name: String,
+    },
+    ImplementationOfNonDrone(NodeId),
+    /// the id of a drone that crashed, whose log and stats are kept.
+    CrashedId(NodeId),
+}
+
+/// Implementations chosen for single drones in the optional
+/// `[[implementation]]` tables of a topology file:
+/// ```toml
+/// [[implementation]]
+/// drone = 3
+/// name = "Lockheed Rustin"
+/// ```
+/// Every other table of the file is ignored.
+#[derive(Deserialize, Debug, Default)]
+struct ImplementationsFile {
+    #[serde(default, rename = "implementation")]
+    implementations: Vec<ImplementationEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ImplementationEntry {
+    drone: NodeId,
+    name: String,
+}
+
+/// Reads, validates and initializes the topology stored at `path`. Drones
+/// get the implementation chosen for them in `implementations`, or in the
+/// topology file, and the ones of `drone_mode` otherwise.
+/// # Errors
+/// Returns an error if the file can't be read or parsed, if the topology
+/// violates the protocol or if the network can't be initialized.
+pub fn load_simulation_controller(
+    path: &Path,
+    drone_mode: DroneMode,
+    implementations: &BTreeMap<NodeId, DroneImplementation>,
+) -> Result<SimulationController, TopologyError> {
+    let file_str = fs::read_to_string(path).map_err(TopologyError::Read)?;
+    let config: Config = toml::from_str(&file_str).map_err(TopologyError::Parse)?;
+    validate(&config).map_err(TopologyError::Invalid)?;
+    let mut chosen = read_implementations(&file_str, &config)?;
+    chosen.extend(
+        implementations
+            .iter()
+            .filter(|(id, _)| config.drone.iter().any(|d| d.id == **id))
+            .map(|(id, implementation)| (*id, implementation.clone())),
+    );
+
+    let sc = if chosen.is_empty() {
+        match drone_mode {
+            DroneMode::Fair => init_network(&config),
+            DroneMode::LockheedRustin => {
+                init_network_with_drone::<LockheedRustin>(&config, "Lockheed Rustin".to_string())
+            }
+        }
+    } else {
+        // every drone is built with its implementation when the network is
+        // initialized, the ones without a factory get a random fair drone
+        let default = match drone_mode {
+            DroneMode::Fair => None,
+            DroneMode::LockheedRustin => Some(DroneImplementation::LockheedRustin),
+        };
+        let mut factories = HashMap::new();
+        for drone in &config.drone {
+            let Some(implementation) = chosen.get(&drone.id).or(default.as_ref()) else {
+                continue;
+            };
+            let factory = implementation.factory().ok_or_else(|| {
+                TopologyError::Init(format!("no drone implementation named {implementation}"))
+            })?;
+            factories.insert(drone.id, factory);
+        }
+        init_network_with_factories(&config, factories)
+    };
+    sc.map_err(|e| TopologyError::Init(format!("{e:?}")))
+}
+
+/// Returns the ids of the drones in the topology stored at `path`, sorted.
+/// # Errors
+/// Returns an error if the file can't be read or parsed.
+pub fn read_drone_ids(path: &Path) -> Result<Vec<NodeId>, TopologyError> {
+    let file_str = fs::read_to_string(path).map_err(TopologyError::Read)?;
+    let config: Config = toml::from_str(&file_str).map_err(TopologyError::Parse)?;
+    let mut ids: Vec<NodeId> = config.drone.iter().map(|d| d.id).collect();
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// reads the implementations chosen in the topology file.
+fn read_implementations(
+    file_str: &str,
+    config: &Config,
+) -> Result<BTreeMap<NodeId, DroneImplementation>, TopologyError> {
+    let file: ImplementationsFile = toml::from_str(file_str).map_err(TopologyError::Parse)?;
+    let mut issues = vec![];
+    let mut chosen = BTreeMap::new();
+    for entry in file.implementations {
+        if !config.drone.iter().any(|d| d.id == entry.drone) {
+            issues.push(TopologyIssue::ImplementationOfNonDrone(entry.drone));
+        } else if let Some(implementation) = DroneImplementation::from_name(&entry.name) {
+            chosen.insert(entry.drone, implementation);
+        } else {
+            issues.push(TopologyIssue::UnknownImplementation {
+                id: entry.drone,
+                name: entry.name,
+            });
+        }
+    }
+    if issues.is_empty() {
+        Ok(chosen)
+    } else {
+        Err(TopologyError::Invalid(issues))
+    }
+}
+
+/// Implementations that a drone can run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DroneImplementation {
+    /// our `LockheedRustin` drone.
+    #[default]
+    LockheedRustin,
+    /// one of the drones bought during the fair, by group name.
+    Fair(String),
+}
+
+impl DroneImplementation {
+    /// Returns every implementation, ours first.
+    #[must_use]
+    pub fn all() -> Vec<Self> {
+        std::iter::once(DroneImplementation::LockheedRustin)
+            .chain(
+                fair_drone_names()
+                    .into_iter()
+                    .map(|name| DroneImplementation::Fair(name.to_string())),
+            )
+            .collect()
+    }
+
+    /// Returns the implementation with the given name, as shown by `Display`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|i| i.to_string() == name)
+    }
+
+    /// returns the factory that builds drones of this implementation when
+    /// the network is initialized.
+    fn factory(&self) -> Option<DroneFactory> {
+        match self {
+            DroneImplementation::LockheedRustin => Some(drone_factory::<LockheedRustin>(
+                "Lockheed Rustin".to_string(),
+            )),
+            DroneImplementation::Fair(name) => fair_drone_factory(name),
+        }
+    }
+}
+
+/// Validates and spawns a new drone in a running simulation, connected to
+/// `neighbors`. The ids of `crashed` drones can't be used.
+/// # Errors
+/// Returns an error if the drone would violate the protocol or if the
+/// controller can't spawn it.
+pub fn spawn_drone(
+    sc: &mut SimulationController,
+    id: NodeId,
+    pdr: f32,
+    neighbors: &[NodeId],
+    implementation: &DroneImplementation,
+    crashed: &[NodeId],
+) -> Result<(), TopologyError> {
+    validate_new_drone(sc, id, pdr, neighbors, crashed).map_err(TopologyError::Invalid)?;
+    let res = match implementation {
+        DroneImplementation::LockheedRustin => {
+            sc.spawn_drone::<LockheedRustin>(id, pdr, neighbors, "Lockheed Rustin".to_string())
+        }
+        DroneImplementation::Fair(name) => sc.spawn_fair_drone(id, pdr, neighbors, name),
+    };
+    res.map_err(|e| TopologyError::Spawn(format!("{e:?}")))
+}
+
+/// Checks that a new drone can join the running simulation without
+/// violating the protocol. The controller doesn't list `crashed` drones,
+/// but their ids are still taken.
+/// # Errors
+/// Returns every issue found.
+pub fn validate_new_drone(
+    sc: &SimulationController,
+    id: NodeId,
+    pdr: f32,
+    neighbors: &[NodeId],
+    crashed: &[NodeId],
+) -> Result<(), Vec<TopologyIssue>> {
+    let mut issues = vec![];
+    let clients = sc.get_client_ids();
+    let ids: HashSet<NodeId> = sc
+        .get_drone_ids()
+        .into_iter()
+        .chain(clients.iter().copied())
+        .chain(sc.get_server_ids())
+        .collect();
+    if ids.contains(&id) {
+        issues.push(TopologyIssue::DuplicateId(id));
+    } else if crashed.contains(&id) {
+        issues.push(TopologyIssue::CrashedId(id));
+    }
+    if !(0.0..=1.0).contains(&pdr) {
+        issues.push(TopologyIssue::PdrOutOfRange { id, pdr });
+    }
+    for &neighbor in neighbors {
+        if neighbor == id {
+            issues.push(TopologyIssue::SelfLink(id));
+        } else if !ids.contains(&neighbor) {
+            issues.push(TopologyIssue::UnknownNeighbor { id, neighbor });
+        } else if clients.contains(&neighbor) {
+            let count = sc.get_topology().neighbors(neighbor).count() + 1;
+            if count > 2 {
+                issues.push(TopologyIssue::ClientDroneCount {
+                    id: neighbor,
+                    count,
+                });
+            }
+        }
+    }
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// Checks that the topology follows the protocol.
+/// # Errors
+/// Returns every issue found in the topology.
+pub fn validate(config: &Config) -> Result<(), Vec<TopologyIssue>> {
+    let mut issues = vec![];
+
+    // collect node types and adjacency lists
+    let mut types = HashMap::new();
+    let mut neighbors: HashMap<NodeId, &[NodeId]> = HashMap::new();
+    let nodes = config
+        .drone
+        .iter()
+        .map(|d| (d.id, NodeType::Drone, d.connected_node_ids.as_slice()))
+        .chain(
+            config
+                .client
+                .iter()
+                .map(|c| (c.id, NodeType::Client, c.connected_drone_ids.as_slice())),
+        )
+        .chain(
+            config
+                .server
+                .iter()
+                .map(|s| (s.id, NodeType::Server, s.connected_drone_ids.as_slice())),
+        );
+    for (id, node_type, ids) in nodes {
+        if types.insert(id, node_type).is_some() {
+            issues.push(TopologyIssue::DuplicateId(id));
+        } else {
+            neighbors.insert(id, ids);
+        }
+    }
+
+    // links
+    let mut sorted_ids: Vec<NodeId> = neighbors.keys().copied().collect();
+    sorted_ids.sort_unstable();
+    for &id in &sorted_ids {
+        for &neighbor in neighbors[&id] {
+            if neighbor == id {
+                issues.push(TopologyIssue::SelfLink(id));
+            } else if !types.contains_key(&neighbor) {
+                issues.push(TopologyIssue::UnknownNeighbor { id, neighbor });
+            } else if types[&id] != NodeType::Drone && types[&neighbor] != NodeType::Drone {
+                // report each link only once
+                if id < neighbor || !neighbors[&neighbor].contains(&id) {
+                    issues.push(TopologyIssue::ForbiddenLink { id, neighbor });
+                }
+            } else if !neighbors[&neighbor].contains(&id) {
+                issues.push(TopologyIssue::AsymmetricLink { id, neighbor });
+            }
+        }
+    }
+
+    let links_consistent = issues.is_empty();
+
+    // node constraints
+    for client in &config.client {
+        let count = client.connected_drone_ids.len();
+        if !(1..=2).contains(&count) {
+            issues.push(TopologyIssue::ClientDroneCount {
+                id: client.id,
+                count,
+            });
+        }
+    }
+    for server in &config.server {
+        let count = server.connected_drone_ids.len();
+        if count < 2 {
+            issues.push(TopologyIssue::ServerDroneCount {
+                id: server.id,
+                count,
+            });
+        }
+    }
+    for drone in &config.drone {
+        if !(0.0..=1.0).contains(&drone.pdr) {
+            issues.push(TopologyIssue::PdrOutOfRange {
+                id: drone.id,
+                pdr: drone.pdr,
+            });
+        }
+    }
+
+    // reachability, only meaningful if the links are consistent
+    if links_consistent {
+        for client in &config.client {
+            let reachable = reachable_servers(client.id, &types, &neighbors);
+            for server in &config.server {
+                if !reachable.contains(&server.id) {
+                    issues.push(TopologyIssue::Unreachable {
+                        client: client.id,
+                        server: server.id,
+                    });
+                }
+            }
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// returns the ids of the servers that can be reached from `client` passing
+/// only through drones.
+fn reachable_servers(
+    client: NodeId,
+    types: &HashMap<NodeId, NodeType>,
+    neighbors: &HashMap<NodeId, &[NodeId]>,
+) -> HashSet<NodeId> {
+    let mut servers = HashSet::new();
+    let mut visited = HashSet::from([client]);
+    let mut queue = VecDeque::from([client]);
+    while let Some(id) = queue.pop_front() {
+        for &neighbor in neighbors[&id] {
+            if !visited.insert(neighbor) {
+                continue;
+            }
+            match types[&neighbor] {
+                NodeType::Drone => queue.push_back(neighbor),
+                NodeType::Server => {
+                    servers.insert(neighbor);
+                }
+                NodeType::Client => {}
+            }
+        }
+    }
+    servers
+}
+
+impl Display for TopologyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TopologyError::Read(e) => write!(f, "Cannot read the topology file: {e}"),
+            TopologyError::Parse(e) => write!(f, "Cannot parse the topology file: {e}"),
+            TopologyError::Invalid(issues) => {
+                write!(f, "The topology violates the protocol:")?;
+                for issue in issues {
+                    write!(f, "\n  - {issue}")?;
+                }
+                Ok(())
+            }
+            TopologyError::Init(e) => write!(f, "Cannot initialize the network: {e}"),
+            TopologyError::Spawn(e) => write!(f, "Cannot spawn the drone: {e}"),
+        }
+    }
+}
+
+impl Display for TopologyIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TopologyIssue::DuplicateId(id) => write!(f, "id {id} is used by more than one node"),
+            TopologyIssue::UnknownNeighbor { id, neighbor } => {
+                write!(f, "node #{id} is connected to node #{neighbor}, which does not exist")
+            }
+            TopologyIssue::SelfLink(id) => write!(f, "node #{id} is connected to itself"),
+            TopologyIssue::AsymmetricLink { id, neighbor } => write!(
+                f,
+                "node #{id} is connected to node #{neighbor}, but not the other way around"
+            ),
+            TopologyIssue::ClientDroneCount { id, count } => write!(
+                f,
+                "client #{id} is connected to {count} drones, it must be connected to 1 or 2"
+            ),
+            TopologyIssue::ServerDroneCount { id, count } => write!(
+                f,
+                "server #{id} is connected to {count} drones, it must be connected to at least 2"
+            ),
+            TopologyIssue::ForbiddenLink { id, neighbor } => write!(
+                f,
+                "node #{id} is connected to node #{neighbor}, clients and servers can only be connected to drones"
+            ),
+            TopologyIssue::PdrOutOfRange { id, pdr } => {
+                write!(f, "drone #{id} has pdr {pdr}, it must be between 0 and 1")
+            }
+            TopologyIssue::Unreachable { client, server } => {
+                write!(f, "client #{client} cannot reach server #{server}")
+            }
+            TopologyIssue::UnknownImplementation { id, name } => {
+                write!(f, "drone #{id} uses implementation \"{name}\", which does not exist")
+            }
+            TopologyIssue::ImplementationOfNonDrone(id) => {
+                write!(f, "an implementation is chosen for node #{id}, which is not a drone")
+            }
+            TopologyIssue::CrashedId(id) => write!(
+                f,
+                "id {id} belongs to a drone that crashed, its log and stats are kept"
+            ),
+        }
+    }
+}
+
+impl Display for DroneImplementation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DroneImplementation::LockheedRustin => write!(f, "Lockheed Rustin"),
+            DroneImplementation::Fair(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a valid topology: client 10 reaches server 20 through drones 1 and 2.
+    const VALID: &str = r"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 10, 20]
+        pdr = 0.1
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 20]
+        pdr = 0.0
+
+        [[client]]
+        id = 10
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 20
+        connected_drone_ids = [1, 2]
+    ";
+
+    fn issues(topology: &str) -> Vec<TopologyIssue> {
+        let config: Config = toml::from_str(topology).unwrap();
+        validate(&config).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn valid_topology() {
+        assert_eq!(issues(VALID), vec![]);
+    }
+
+    #[test]
+    fn direct_client_server_link() {
+        let topology = VALID
+            .replace(
+                "connected_drone_ids = [1]\n",
+                "connected_drone_ids = [1, 20]\n",
+            )
+            .replace(
+                "connected_drone_ids = [1, 2]",
+                "connected_drone_ids = [1, 2, 10]",
+            );
+        // the link is reported once, from the side with the smallest id
+        assert_eq!(
+            issues(&topology),
+            vec![TopologyIssue::ForbiddenLink {
+                id: 10,
+                neighbor: 20
+            }]
+        );
+    }
+
+    #[test]
+    fn asymmetric_link() {
+        let topology = VALID.replace("connected_node_ids = [1, 20]", "connected_node_ids = [20]");
+        assert_eq!(
+            issues(&topology),
+            vec![TopologyIssue::AsymmetricLink { id: 1, neighbor: 2 }]
+        );
+    }
+
+    #[test]
+    fn self_link() {
+        let topology = VALID.replace(
+            "connected_node_ids = [1, 20]",
+            "connected_node_ids = [1, 2, 20]",
+        );
+        assert_eq!(issues(&topology), vec![TopologyIssue::SelfLink(2)]);
+    }
+
+    #[test]
+    fn unknown_neighbor() {
+        let topology = VALID.replace(
+            "connected_node_ids = [1, 20]",
+            "connected_node_ids = [1, 20, 99]",
+        );
+        assert_eq!(
+            issues(&topology),
+            vec![TopologyIssue::UnknownNeighbor {
+                id: 2,
+                neighbor: 99
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_id() {
+        let topology = format!("{VALID}\n[[client]]\nid = 1\nconnected_drone_ids = [2]\n");
+        assert!(issues(&topology).contains(&TopologyIssue::DuplicateId(1)));
+    }
+
+    #[test]
+    fn client_with_too_many_drones() {
+        let topology = r"
+            [[drone]]
+            id = 1
+            connected_node_ids = [2, 3, 10, 20]
+            pdr = 0.0
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [1, 10, 20]
+            pdr = 0.0
+
+            [[drone]]
+            id = 3
+            connected_node_ids = [1, 10]
+            pdr = 0.0
+
+            [[client]]
+            id = 10
+            connected_drone_ids = [1, 2, 3]
+
+            [[server]]
+            id = 20
+            connected_drone_ids = [1, 2]
+        ";
+        assert_eq!(
+            issues(topology),
+            vec![TopologyIssue::ClientDroneCount { id: 10, count: 3 }]
+        );
+    }
+
+    #[test]
+    fn server_with_one_drone() {
+        let topology = VALID
+            .replace("connected_node_ids = [1, 20]", "connected_node_ids = [1]")
+            .replace("connected_drone_ids = [1, 2]", "connected_drone_ids = [1]");
+        assert_eq!(
+            issues(&topology),
+            vec![TopologyIssue::ServerDroneCount { id: 20, count: 1 }]
+        );
+    }
+
+    #[test]
+    fn pdr_out_of_range() {
+        let topology = VALID.replace("pdr = 0.1", "pdr = 1.5");
+        assert_eq!(
+            issues(&topology),
+            vec![TopologyIssue::PdrOutOfRange { id: 1, pdr: 1.5 }]
+        );
+    }
+
+    #[test]
+    fn unreachable_server() {
+        let topology = r"
+            [[drone]]
+            id = 1
+            connected_node_ids = [10]
+            pdr = 0.0
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [3, 20]
+            pdr = 0.0
+
+            [[drone]]
+            id = 3
+            connected_node_ids = [2, 20]
+            pdr = 0.0
+
+            [[client]]
+            id = 10
+            connected_drone_ids = [1]
+
+            [[server]]
+            id = 20
+            connected_drone_ids = [2, 3]
+        ";
+        assert_eq!(
+            issues(topology),
+            vec![TopologyIssue::Unreachable {
+                client: 10,
+                server: 20
+            }]
+        );
+    }
+
+    #[test]
+    fn reachability_ignores_paths_through_clients() {
+        // client 11 links the two halves, but packets can't cross clients
+        let topology = r"
+            [[drone]]
+            id = 1
+            connected_node_ids = [10, 11]
+            pdr = 0.0
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [3, 11, 20]
+            pdr = 0.0
+
+            [[drone]]
+            id = 3
+            connected_node_ids = [2, 20]
+            pdr = 0.0
+
+            [[client]]
+            id = 10
+            connected_drone_ids = [1]
+
+            [[client]]
+            id = 11
+            connected_drone_ids = [1, 2]
+
+            [[server]]
+            id = 20
+            connected_drone_ids = [2, 3]
+        ";
+        assert_eq!(
+            issues(topology),
+            vec![TopologyIssue::Unreachable {
+                client: 10,
+                server: 20
+            }]
+        );
+    }
+
+    #[test]
+    fn implementation_of_a_choke_point() {
+        // drone 1 is the only drone of client 10
+        let topology = r#"
+            [[drone]]
+            id = 1
+            connected_node_ids = [2, 3, 10]
+            pdr = 0.0
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [1, 20]
+            pdr = 0.0
+
+            [[drone]]
+            id = 3
+            connected_node_ids = [1, 20]
+            pdr = 0.0
+
+            [[client]]
+            id = 10
+            connected_drone_ids = [1]
+
+            [[server]]
+            id = 20
+            connected_drone_ids = [2, 3]
+
+            [[implementation]]
+            drone = 1
+            name = "Lockheed Rustin"
+        "#;
+        let path = std::env::temp_dir().join("controller_choke_point_topology.toml");
+        fs::write(&path, topology).unwrap();
+        let implementations = BTreeMap::from([(2, DroneImplementation::LockheedRustin)]);
+        let sc = load_simulation_controller(&path, DroneMode::Fair, &implementations);
+        fs::remove_file(&path).unwrap();
+        let sc = sc.unwrap();
+
+        let mut drones = sc.get_drone_ids();
+        drones.sort_unstable();
+        assert_eq!(drones, vec![1, 2, 3]);
+        for id in [1, 2] {
+            assert_eq!(
+                sc.get_group_name(id).map(|name| name.to_string()),
+                Some("Lockheed Rustin".to_string())
+            );
+        }
+        // no link was lost while the drones were built
+        assert!(sc.get_topology().contains_edge(1, 10));
+        assert!(sc.get_topology().contains_edge(1, 2));
+        assert!(sc.get_topology().contains_edge(3, 20));
+    }
+}
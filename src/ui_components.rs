@@ -1,9 +1,35 @@
+pub mod add_drone_window;
+pub mod bulk_window;
+pub mod chaos_window;
+pub mod charts;
 pub mod client_window;
 pub mod custom_edge;
 pub mod custom_node;
 pub mod drone_window;
+pub mod edge_menu;
+pub mod fault_safety;
 pub mod file_window;
+pub mod heatmap;
+pub mod history_window;
+pub mod implementations_window;
 pub mod logs;
+pub mod node_tooltip;
+pub mod packet_animation;
+pub mod path_overlay;
+pub mod scenario_window;
+pub mod selection;
 pub mod server_window;
 pub mod stats;
 pub mod text;
+pub mod timeline;
+pub mod topology_error_window;
+pub mod trace_window;
+
+use eframe::egui::Id;
+use wg_2024::network::NodeId;
+
+/// Returns the id of the window of a node, which doesn't change with its
+/// title.
+pub fn node_window_id(id: NodeId) -> Id {
+    Id::new(("node_window", id))
+}
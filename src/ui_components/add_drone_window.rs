@@ -0,0 +1,100 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{vec2, Color32, ComboBox, Context, DragValue, RichText, Slider, Window};
+
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+use crate::app::simulation_controller_ui::AddDroneWindowState;
+use crate::shared_data::SimulationData;
+use crate::topology::DroneImplementation;
+use crate::ui_components::text::spawn_white_heading;
+
+/// Spawns the window for adding a drone to the running simulation.
+pub fn spawn(
+    ctx: &Context,
+    mutex: &mut MutexGuard<SimulationData>,
+    node_ids: &[NodeId],
+    state: &mut AddDroneWindowState,
+) {
+    let mut open = state.open;
+    Window::new("Add drone")
+        .open(&mut open)
+        .default_size(vec2(350.0, 250.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.monospace("Id:");
+                ui.add(DragValue::new(&mut state.id));
+            });
+            ui.add_space(3.0);
+            ui.horizontal(|ui| {
+                ui.monospace("PDR:");
+                ui.add(Slider::new(&mut state.pdr, 0.0..=1.0));
+            });
+            ui.add_space(3.0);
+            ui.horizontal(|ui| {
+                ui.monospace("Implementation:");
+                ComboBox::from_id_salt("add_drone_implementation")
+                    .selected_text(state.implementation.to_string())
+                    .show_ui(ui, |ui| {
+                        for implementation in DroneImplementation::all() {
+                            let text = implementation.to_string();
+                            ui.selectable_value(&mut state.implementation, implementation, text);
+                        }
+                    });
+            });
+            ui.add_space(5.0);
+
+            spawn_white_heading(ui, "Neighbors");
+            ui.add_space(5.0);
+            ui.horizontal_wrapped(|ui| {
+                for &id in node_ids {
+                    let name = match mutex.node_type(id) {
+                        Some(NodeType::Client) => "Client",
+                        Some(NodeType::Server) => "Server",
+                        _ => "Drone",
+                    };
+                    let mut checked = state.neighbors.contains(&id);
+                    if ui.checkbox(&mut checked, format!("{name} #{id}")).changed() {
+                        if checked {
+                            state.neighbors.insert(id);
+                        } else {
+                            state.neighbors.remove(&id);
+                        }
+                    }
+                }
+            });
+            ui.add_space(5.0);
+
+            if ui.button("Spawn").clicked() {
+                // neighbors that crashed since they were checked are ignored
+                state.neighbors.retain(|id| node_ids.contains(id));
+                let neighbors: Vec<NodeId> = state.neighbors.iter().copied().collect();
+                match mutex.spawn_drone(state.id, state.pdr, &neighbors, &state.implementation) {
+                    Ok(()) => {
+                        state.error = None;
+                        state.neighbors.clear();
+                        state.id = first_free_id(&mutex.get_known_ids());
+                    }
+                    Err(e) => state.error = Some(e),
+                }
+            }
+            if let Some(e) = &state.error {
+                ui.label(
+                    RichText::new(e.to_string())
+                        .monospace()
+                        .color(Color32::LIGHT_RED),
+                );
+            }
+        });
+    state.open = open;
+}
+
+/// returns the smallest id that is not used by any node, crashed drones
+/// included. If every id is used, the spawn fails and the duplicate id is
+/// reported.
+pub fn first_free_id(node_ids: &[NodeId]) -> NodeId {
+    (0..=NodeId::MAX)
+        .find(|id| !node_ids.contains(id))
+        .unwrap_or_default()
+}
@@ -0,0 +1,199 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use eframe::egui::{vec2, Button, ComboBox, Context, DragValue, RichText, Slider, Ui, Window};
+
+use wg_2024::network::NodeId;
+
+use crate::app::simulation_controller_ui::BulkWindowState;
+use crate::ui_components::fault_safety::{SafetyFrame, UNSAFE_COLOR};
+use crate::ui_components::selection::Selection;
+use crate::ui_components::text::spawn_white_heading;
+
+/// An action on the selected drones asked by the user.
+#[derive(Debug)]
+pub enum BulkRequest {
+    SetPdr(f32),
+    ClearLogs,
+    /// crash the drones in this order, waiting between two crashes.
+    Crash(Vec<NodeId>, Duration),
+    StopCrashing,
+}
+
+/// Spawns the window of the actions on all the selected drones. Returns the
+/// action asked by the user, if any.
+/// #Arguments
+/// `drones` maps the id of each drone to its group name, `safety` holds
+/// the drones whose crash would break routes now, `crashing` tells whether
+/// a sequence of crashes is running.
+pub fn spawn(
+    ctx: &Context,
+    safety: &SafetyFrame,
+    state: &mut BulkWindowState,
+    selection: &mut Selection,
+    drones: &BTreeMap<NodeId, String>,
+    crashing: bool,
+) -> Option<BulkRequest> {
+    // keep the crash order in sync with the selection
+    state.crash_order.retain(|id| selection.drones.contains(id));
+    for &id in &selection.drones {
+        if !state.crash_order.contains(&id) {
+            state.crash_order.push(id);
+        }
+    }
+
+    let mut request = None;
+    let mut open = state.open;
+    Window::new("Bulk actions")
+        .open(&mut open)
+        .default_size(vec2(350.0, 350.0))
+        .show(ctx, |ui| {
+            spawn_selectors(ui, state, selection, drones);
+            let any = !selection.drones.is_empty();
+            ui.add_space(5.0);
+
+            spawn_white_heading(ui, "Actions");
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.monospace("PDR:");
+                ui.add(Slider::new(&mut state.pdr, 0.0..=1.0));
+                if ui.add_enabled(any, Button::new("Set")).clicked() {
+                    request = Some(BulkRequest::SetPdr(state.pdr));
+                }
+            });
+            ui.add_space(3.0);
+            if ui.add_enabled(any, Button::new("Clear logs")).clicked() {
+                request = Some(BulkRequest::ClearLogs);
+            }
+            ui.add_space(5.0);
+
+            spawn_white_heading(ui, "Crash in order");
+            ui.add_space(5.0);
+            if let Some(r) = spawn_crash_order(ui, safety, state, drones, crashing) {
+                request = Some(r);
+            }
+        });
+    state.open = open;
+    request
+}
+
+/// Spawns the list of selected drones and the buttons to change it.
+fn spawn_selectors(
+    ui: &mut Ui,
+    state: &mut BulkWindowState,
+    selection: &mut Selection,
+    drones: &BTreeMap<NodeId, String>,
+) {
+    if selection.drones.is_empty() {
+        ui.label(
+            "No drone selected. Tick drones in the sidebar, or shift-click and \
+            shift-drag in the Topology section.",
+        );
+    } else {
+        let list = selection
+            .drones
+            .iter()
+            .map(|id| format!("#{id}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        ui.label(format!("Selected drones: {list}"));
+    }
+    ui.add_space(3.0);
+    ui.horizontal(|ui| {
+        if ui.button("Select all").clicked() {
+            selection.drones.extend(drones.keys());
+        }
+        if ui.button("Clear selection").clicked() {
+            selection.drones.clear();
+        }
+    });
+    ui.add_space(3.0);
+
+    let groups: BTreeSet<&String> = drones.values().collect();
+    if state.group.as_ref().is_some_and(|g| !groups.contains(g)) {
+        state.group = None;
+    }
+    ui.horizontal(|ui| {
+        ui.monospace("Group:");
+        ComboBox::from_id_salt("bulk_group_combobox")
+            .selected_text(state.group.as_deref().unwrap_or("-"))
+            .show_ui(ui, |ui| {
+                for group in groups {
+                    ui.selectable_value(&mut state.group, Some(group.clone()), group);
+                }
+            });
+        if ui
+            .add_enabled(state.group.is_some(), Button::new("Select group"))
+            .clicked()
+        {
+            let group = state.group.as_ref();
+            selection.drones.extend(
+                drones
+                    .iter()
+                    .filter(|(_, name)| Some(*name) == group)
+                    .map(|(id, _)| *id),
+            );
+        }
+    });
+}
+
+/// Spawns the order in which the selected drones are crashed, with the
+/// delay between two crashes and the button starting or stopping them.
+fn spawn_crash_order(
+    ui: &mut Ui,
+    safety: &SafetyFrame,
+    state: &mut BulkWindowState,
+    drones: &BTreeMap<NodeId, String>,
+    crashing: bool,
+) -> Option<BulkRequest> {
+    let mut swap = None;
+    let last = state.crash_order.len().saturating_sub(1);
+    for (i, id) in state.crash_order.iter().enumerate() {
+        ui.horizontal(|ui| {
+            if ui.add_enabled(i > 0, Button::new("⬆")).clicked() {
+                swap = Some((i - 1, i));
+            }
+            if ui.add_enabled(i < last, Button::new("⬇")).clicked() {
+                swap = Some((i, i + 1));
+            }
+            let name = drones.get(id).map_or("Drone", String::as_str);
+            let text = RichText::new(format!("{}. {name} #{id}", i + 1)).monospace();
+            if safety.drones.contains(id) {
+                ui.label(text.color(UNSAFE_COLOR))
+                    .on_hover_text("crashing this drone now would break routes");
+            } else {
+                ui.label(text);
+            }
+        });
+    }
+    if let Some((a, b)) = swap {
+        state.crash_order.swap(a, b);
+    }
+    ui.add_space(3.0);
+
+    ui.horizontal(|ui| {
+        ui.monospace("Delay:");
+        ui.add(
+            DragValue::new(&mut state.crash_delay)
+                .range(0.0..=600.0)
+                .speed(0.1)
+                .suffix(" s"),
+        );
+        if crashing {
+            ui.label("Crashing…");
+            ui.button("Stop")
+                .clicked()
+                .then_some(BulkRequest::StopCrashing)
+        } else {
+            ui.add_enabled(!state.crash_order.is_empty(), Button::new("Crash"))
+                .clicked()
+                .then(|| {
+                    BulkRequest::Crash(
+                        state.crash_order.clone(),
+                        Duration::from_secs_f64(state.crash_delay),
+                    )
+                })
+        }
+    })
+    .inner
+}
@@ -0,0 +1,115 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{vec2, Color32, Context, DragValue, RichText, ScrollArea, Slider, Window};
+
+use crate::app::simulation_controller_ui::ChaosState;
+use crate::shared_data::SimulationData;
+use crate::ui_components::text::spawn_white_heading;
+
+/// What the user asked to do with the chaos mode.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChaosRequest {
+    Start,
+    Stop,
+}
+
+/// Spawns the window of the chaos mode, with its settings and the faults
+/// it injected. Returns the request of the user, if any.
+pub fn spawn(
+    ctx: &Context,
+    mutex: &MutexGuard<SimulationData>,
+    state: &mut ChaosState,
+) -> Option<ChaosRequest> {
+    let mut request = None;
+    let running = state.thread.is_some();
+    let config = &mut state.config;
+    Window::new("Chaos mode")
+        .open(&mut state.window_open)
+        .default_size(vec2(400.0, 350.0))
+        .show(ctx, |ui| {
+            ui.label(
+                "Injects a random fault at a fixed rate. The same seed injects the same \
+                faults when started on the same topology.",
+            );
+            ui.add_space(5.0);
+
+            spawn_white_heading(ui, "Settings");
+            ui.add_space(5.0);
+            ui.add_enabled_ui(!running, |ui| {
+                ui.horizontal(|ui| {
+                    ui.monospace("Seed:");
+                    ui.add(DragValue::new(&mut config.seed));
+                    if ui.button("Random").clicked() {
+                        config.seed = rand::random::<u32>().into();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.monospace("Interval:");
+                    ui.add(
+                        DragValue::new(&mut config.interval)
+                            .range(0.1..=600.0)
+                            .speed(0.1)
+                            .suffix(" s"),
+                    );
+                });
+                ui.horizontal_wrapped(|ui| {
+                    ui.monospace("Faults:");
+                    ui.checkbox(&mut config.raise_pdr, "Raise PDR");
+                    ui.checkbox(&mut config.remove_link, "Remove link");
+                    ui.checkbox(&mut config.crash, "Crash non-critical drone");
+                    ui.checkbox(&mut config.add_link, "Add link");
+                });
+                ui.horizontal(|ui| {
+                    ui.monospace("Max PDR:");
+                    ui.add(Slider::new(&mut config.max_pdr, 0.0..=1.0));
+                });
+            });
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                if running {
+                    if ui.button("Stop").clicked() {
+                        request = Some(ChaosRequest::Stop);
+                    }
+                } else if ui.button("Start").clicked() {
+                    request = Some(ChaosRequest::Start);
+                }
+                match (running, state.last_seed) {
+                    (true, Some(seed)) => {
+                        ui.label(format!("Running with seed {seed}"));
+                    }
+                    (false, Some(seed)) => {
+                        ui.label(format!("Stopped, last seed {seed}"));
+                    }
+                    _ => {}
+                }
+            });
+            ui.add_space(5.0);
+
+            let reports = &mutex.chaos_reports;
+            spawn_white_heading(ui, "Injected faults");
+            ui.add_space(5.0);
+            ui.group(|ui| {
+                ScrollArea::vertical()
+                    .max_height(200.0)
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for report in reports {
+                            let (line, color) = if report.applied {
+                                (
+                                    format!("{:>7.2}s {}", report.at, report.fault),
+                                    Color32::LIGHT_GREEN,
+                                )
+                            } else {
+                                (
+                                    format!("{:>7.2}s {} (failed)", report.at, report.fault),
+                                    Color32::LIGHT_RED,
+                                )
+                            };
+                            ui.label(RichText::new(line).monospace().color(color));
+                        }
+                    });
+            });
+        });
+    request
+}
@@ -0,0 +1,152 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{Color32, Ui};
+use egui_plot::{Legend, Line, Plot, PlotPoints, VLine};
+
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+use crate::pdr_schedule::TICK;
+use crate::shared_data::{Series, SimulationData, TimeSeries};
+use crate::ui_components::text::spawn_white_heading;
+
+/// height of the charts shown in node windows.
+const NODE_CHART_HEIGHT: f32 = 110.0;
+/// height of the charts shown in the dashboard.
+const DASHBOARD_CHART_HEIGHT: f32 = 200.0;
+
+/// color of the PDR schedule being applied.
+const ACTIVE_SCHEDULE_COLOR: Color32 = Color32::LIGHT_GREEN;
+
+/// Colors used for the lines of each node in the dashboard.
+const PALETTE: [Color32; 8] = [
+    Color32::LIGHT_BLUE,
+    Color32::LIGHT_GREEN,
+    Color32::LIGHT_RED,
+    Color32::LIGHT_YELLOW,
+    Color32::from_rgb(200, 150, 255),
+    Color32::from_rgb(255, 170, 90),
+    Color32::from_rgb(120, 220, 220),
+    Color32::from_rgb(255, 150, 200),
+];
+
+/// spawns the rolling chart of the events per second of the node with
+/// given id.
+pub fn spawn_node(ui: &mut Ui, mutex: &MutexGuard<SimulationData>, id: NodeId) {
+    let series: &[Series] = match mutex.node_type(id) {
+        Some(NodeType::Drone) => &[Series::Fragments, Series::Drops, Series::Nacks],
+        _ => &[
+            Series::Fragments,
+            Series::Nacks,
+            Series::MessagesFragmented,
+            Series::MessagesAssembled,
+        ],
+    };
+    spawn_white_heading(ui, "Per second");
+    let empty = TimeSeries::default();
+    let time_series = mutex.get_series(id).unwrap_or(&empty);
+    let now = mutex.current_second();
+    chart(("node_chart", id), NODE_CHART_HEIGHT).show(ui, |plot_ui| {
+        for &s in series {
+            plot_ui.line(
+                Line::new(PlotPoints::from(time_series.points(s, now)))
+                    .name(s.name())
+                    .color(series_color(s)),
+            );
+        }
+    });
+}
+
+/// spawns the charts of the whole network: every series summed over all
+/// nodes, then forwarded and dropped fragments with a line for each drone.
+pub fn spawn_dashboard(ui: &mut Ui, mutex: &MutexGuard<SimulationData>, drone_ids: &[NodeId]) {
+    let now = mutex.current_second();
+
+    spawn_white_heading(ui, "Network");
+    let global = mutex.get_global_series();
+    chart("dashboard_global", DASHBOARD_CHART_HEIGHT).show(ui, |plot_ui| {
+        for s in Series::ALL {
+            plot_ui.line(
+                Line::new(PlotPoints::from(global.points(s, now)))
+                    .name(s.name())
+                    .color(series_color(s)),
+            );
+        }
+    });
+    ui.add_space(10.0);
+
+    for (title, series) in [
+        ("Forwarded fragments by drone", Series::Fragments),
+        ("Dropped fragments by drone", Series::Drops),
+    ] {
+        spawn_white_heading(ui, title);
+        chart(("dashboard_drones", series.name()), DASHBOARD_CHART_HEIGHT).show(ui, |plot_ui| {
+            for (i, &id) in drone_ids.iter().enumerate() {
+                let Some(time_series) = mutex.get_series(id) else {
+                    continue;
+                };
+                plot_ui.line(
+                    Line::new(PlotPoints::from(time_series.points(series, now)))
+                        .name(format!("#{id}"))
+                        .color(PALETTE[i % PALETTE.len()]),
+                );
+            }
+        });
+        ui.add_space(10.0);
+    }
+}
+
+/// spawns the preview of the PDR schedule being edited in the window of
+/// the drone with given id and, if any, of the one being applied with the
+/// seconds elapsed since it started.
+pub fn spawn_pdr_schedule(ui: &mut Ui, id: NodeId, edited: &[f32], active: Option<(&[f32], f64)>) {
+    chart(("pdr_schedule_chart", id), NODE_CHART_HEIGHT).show(ui, |plot_ui| {
+        plot_ui.line(
+            Line::new(schedule_points(edited))
+                .name("Edited")
+                .color(Color32::GRAY),
+        );
+        if let Some((samples, elapsed)) = active {
+            plot_ui.line(
+                Line::new(schedule_points(samples))
+                    .name("Running")
+                    .color(ACTIVE_SCHEDULE_COLOR),
+            );
+            plot_ui.vline(VLine::new(elapsed).color(ACTIVE_SCHEDULE_COLOR));
+        }
+    });
+}
+
+/// returns the points of a PDR schedule, one for each tick.
+fn schedule_points(samples: &[f32]) -> PlotPoints {
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, &pdr)| [i as f64 * TICK.as_secs_f64(), f64::from(pdr)])
+        .collect()
+}
+
+/// returns a non interactive chart, with the count axis starting at zero.
+fn chart(id: impl std::hash::Hash, height: f32) -> Plot<'static> {
+    Plot::new(id)
+        .height(height)
+        .legend(Legend::default())
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .include_y(0.0)
+        .include_y(1.0)
+        .x_axis_label("s")
+}
+
+/// returns the color of the line of a series.
+fn series_color(series: Series) -> Color32 {
+    match series {
+        Series::Fragments => Color32::LIGHT_BLUE,
+        Series::Drops => Color32::LIGHT_RED,
+        Series::Nacks => Color32::LIGHT_YELLOW,
+        Series::MessagesFragmented => Color32::from_rgb(200, 150, 255),
+        Series::MessagesAssembled => Color32::LIGHT_GREEN,
+    }
+}
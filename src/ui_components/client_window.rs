@@ -2,12 +2,12 @@ use drone_network::message::ClientBody::{ClientCommunication, ClientContent};
 use drone_network::message::{
     ClientBody, ClientCommunicationBody, ClientContentBody, CommunicationMessage,
 };
-use eframe::egui::{vec2, Color32, ComboBox, Context, TextEdit, TextStyle, Ui, Window};
+use eframe::egui::{vec2, ComboBox, Context, TextEdit, TextStyle, Ui, Window};
 use std::fmt::{Display, Formatter};
 use std::sync::MutexGuard;
 
 use crate::app::simulation_controller_ui::ClientWindowState;
-use crate::shared_data::SimulationData;
+use crate::shared_data::{LogEntry, LogKind, Severity, SimulationData};
 use crate::ui_components;
 use wg_2024::network::NodeId;
 
@@ -53,12 +53,20 @@ pub fn spawn(
     state: &mut ClientWindowState,
 ) {
     Window::new(format!("Client #{id}"))
+        .id(ui_components::node_window_id(id))
         .open(open)
-        .fixed_size(vec2(400.0, 300.0))
+        .default_size(vec2(400.0, 300.0))
+        .resizable(true)
+        .vscroll(true)
         .show(ctx, |ui| {
             ui_components::stats::spawn_client(ui, mutex, id);
+            ui_components::charts::spawn_node(ui, mutex, id);
+            ui.separator();
             // logs
-            ui_components::logs::spawn(ui, mutex, id);
+            if let Some(session) = ui_components::logs::spawn(ui, mutex, id, &mut state.log_filter)
+            {
+                state.traced_session = Some(session);
+            }
             if ui.button("Clear log").clicked() {
                 mutex.clear_log(id);
             }
@@ -70,6 +78,7 @@ pub fn spawn(
 
             spawn_message_combobox(ui, mutex, id, client_ids, server_ids, state);
         });
+    ui_components::trace_window::spawn(ctx, mutex, id, &mut state.traced_session);
 }
 
 /// Spawns the form for choosing the message to be sent.
@@ -306,8 +315,13 @@ fn spawn_send_button(
     state: &mut ClientWindowState,
 ) {
     if ui.button("Send").clicked() {
-        let log_line = match send(mutex, id, state) {
-            None => "Error in sending command".to_string(),
+        let entry = match send(mutex, id, state) {
+            None => LogEntry::new(
+                id,
+                LogKind::ControllerAction,
+                "Error in sending command".to_string(),
+            )
+            .with_severity(Severity::Error),
             Some(()) => {
                 state.message_choice = MessageChoice::NotChosen;
                 state.content_choice = ContentChoice::NotChosen;
@@ -315,10 +329,14 @@ fn spawn_send_button(
                 state.server_destination_id = None;
                 state.client_destination_id = None;
                 state.text_input.clear();
-                "ClientCommand sent correctly".to_string()
+                LogEntry::new(
+                    id,
+                    LogKind::ControllerAction,
+                    "ClientCommand sent correctly".to_string(),
+                )
             }
         };
-        mutex.add_log(id, log_line, Color32::GRAY);
+        mutex.add_log(entry);
     }
 }
 
@@ -1,9 +1,23 @@
-use eframe::egui::{Color32, Pos2, Shape, Stroke};
+use eframe::egui::{Color32, Context, Id, Pos2, Shape, Stroke};
 use egui_graphs::{DefaultEdgeShape, DisplayEdge, DisplayNode, DrawContext, EdgeProps, Node};
 use petgraph::{stable_graph::IndexType, EdgeType};
 
+use wg_2024::network::NodeId;
+use wg_2024::packet::NodeType;
+
+use crate::ui_components::fault_safety::{SafetyOverlay, UNSAFE_COLOR};
+use crate::ui_components::heatmap::{heat_color, HeatmapState};
+use crate::ui_components::packet_animation::AnimationState;
+use crate::ui_components::path_overlay::{PathOverlay, PATH_COLOR};
+
 const COLOR: Color32 = Color32::from_rgb(70, 70, 70);
 const WIDTH: f32 = 3.0;
+/// width of the busiest edge in the heatmap, relative to `WIDTH`.
+const HEATMAP_WIDTH_SCALE: f32 = 3.0;
+const DOT_RADIUS: f32 = 2.5;
+/// distance from the stroke, in screen points, within which a right click
+/// still hits an edge.
+const CLICK_TOLERANCE: f32 = 4.0;
 
 /// Struct for rendering a custom edge in the Topology section
 #[derive(Clone)]
@@ -19,28 +33,85 @@ impl<E: Clone> From<EdgeProps<E>> for EdgeShape {
     }
 }
 
-impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, Ix>>
-    DisplayEdge<N, E, Ty, Ix, D> for EdgeShape
+impl<E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<(NodeId, NodeType), E, Ty, Ix>>
+    DisplayEdge<(NodeId, NodeType), E, Ty, Ix, D> for EdgeShape
 {
     fn shapes(
         &mut self,
-        start: &Node<N, E, Ty, Ix, D>,
-        end: &Node<N, E, Ty, Ix, D>,
+        start: &Node<(NodeId, NodeType), E, Ty, Ix, D>,
+        end: &Node<(NodeId, NodeType), E, Ty, Ix, D>,
         ctx: &DrawContext,
     ) -> Vec<Shape> {
         let mut res = vec![];
-        let (start, end) = (start.location(), end.location());
+        let (start_id, end_id) = (start.payload().0, end.payload().0);
+        let (start, end) = (
+            ctx.meta.canvas_to_screen_pos(start.location()),
+            ctx.meta.canvas_to_screen_pos(end.location()),
+        );
 
         let mut stroke = Stroke::new(WIDTH, COLOR);
+        if let Some(heatmap) = HeatmapState::load_frame(ctx.ctx) {
+            let key = (start_id.min(end_id), start_id.max(end_id));
+            let intensity = heatmap.edges.get(&key).copied().unwrap_or_default();
+            stroke = Stroke::new(
+                WIDTH * (1.0 + (HEATMAP_WIDTH_SCALE - 1.0) * intensity),
+                heat_color(intensity),
+            );
+        }
 
         stroke.width = ctx.meta.canvas_to_screen_size(stroke.width);
-        res.push(Shape::line_segment(
-            [
-                ctx.meta.canvas_to_screen_pos(start),
-                ctx.meta.canvas_to_screen_pos(end),
-            ],
-            stroke,
-        ));
+        // links whose removal would break something are outlined
+        if let Some(safety) = SafetyOverlay::load_frame(ctx.ctx) {
+            if safety
+                .links
+                .contains(&(start_id.min(end_id), start_id.max(end_id)))
+            {
+                let outline = Stroke::new(stroke.width * 2.5, UNSAFE_COLOR.gamma_multiply(0.6));
+                res.push(Shape::line_segment([start, end], outline));
+            }
+        }
+        // links on the highlighted paths
+        if let Some(paths) = PathOverlay::load_frame(ctx.ctx) {
+            if paths
+                .edges
+                .contains(&(start_id.min(end_id), start_id.max(end_id)))
+            {
+                stroke.color = PATH_COLOR;
+            }
+        }
+        res.push(Shape::line_segment([start, end], stroke));
+
+        // remember the edge under a right click, for the topology section
+        // to offer removing it
+        let right_click = ctx
+            .ctx
+            .input(|i| {
+                i.pointer
+                    .secondary_clicked()
+                    .then(|| i.pointer.interact_pos())
+            })
+            .flatten();
+        if let Some(pointer) = right_click {
+            if distance_to_segment(pointer, start, end) <= stroke.width / 2.0 + CLICK_TOLERANCE {
+                ctx.ctx
+                    .data_mut(|d| d.insert_temp(right_click_id(), (start_id, end_id)));
+            }
+        }
+
+        // packets travelling along the edge, in both directions
+        if let Some(frame) = AnimationState::load_frame(ctx.ctx) {
+            let radius = ctx.meta.canvas_to_screen_size(DOT_RADIUS);
+            let directions = [
+                ((start, end), (start_id, end_id)),
+                ((end, start), (end_id, start_id)),
+            ];
+            for ((from, to), key) in directions {
+                for (progress, color) in frame.dots.get(&key).into_iter().flatten() {
+                    let pos = from.lerp(to, progress.clamp(0.0, 1.0));
+                    res.push(Shape::circle_filled(pos, radius, *color));
+                }
+            }
+        }
 
         res
     }
@@ -49,10 +120,35 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
 
     fn is_inside(
         &self,
-        start: &Node<N, E, Ty, Ix, D>,
-        end: &Node<N, E, Ty, Ix, D>,
+        start: &Node<(NodeId, NodeType), E, Ty, Ix, D>,
+        end: &Node<(NodeId, NodeType), E, Ty, Ix, D>,
         pos: Pos2,
     ) -> bool {
         self.default_impl.is_inside(start, end, pos)
     }
 }
+
+/// Returns the endpoints of the edge that was right-clicked in the last
+/// frame, if any, and forgets it.
+pub fn take_right_clicked(ctx: &Context) -> Option<(NodeId, NodeId)> {
+    ctx.data_mut(|d| {
+        let edge = d.get_temp(right_click_id());
+        d.remove::<(NodeId, NodeId)>(right_click_id());
+        edge
+    })
+}
+
+fn right_click_id() -> Id {
+    Id::new("edge_right_clicked")
+}
+
+/// returns the distance between a point and a segment.
+fn distance_to_segment(point: Pos2, start: Pos2, end: Pos2) -> f32 {
+    let segment = end - start;
+    let length_sq = segment.length_sq();
+    if length_sq == 0.0 {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_sq).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
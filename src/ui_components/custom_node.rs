@@ -1,5 +1,6 @@
 use eframe::egui::{
-    epaint::TextShape, Color32, FontFamily, FontId, Pos2, Shape, Stroke, TextureId, Vec2,
+    epaint::TextShape, Color32, Context, FontFamily, FontId, Id, Pos2, Shape, Stroke, TextureId,
+    Vec2,
 };
 use eframe::epaint::{Rect, RectShape, Rounding};
 use egui_graphs::{DisplayNode, NodeProps};
@@ -8,12 +9,22 @@ use petgraph::{stable_graph::IndexType, EdgeType};
 use wg_2024::network::NodeId;
 use wg_2024::packet::NodeType;
 
+use crate::ui_components::fault_safety::{SafetyOverlay, UNSAFE_COLOR};
+use crate::ui_components::heatmap::{heat_color, HeatmapState};
+use crate::ui_components::packet_animation::AnimationState;
+use crate::ui_components::path_overlay::{PathOverlay, PATH_COLOR};
+use crate::ui_components::selection::{self, Selection, SELECTION_COLOR};
+
 const RADIUS: f32 = 5.0;
 const COLOR: Color32 = Color32::WHITE;
+const BURST_COLOR: Color32 = Color32::RED;
+/// radius reached by a drop burst, relative to the node radius.
+const BURST_SCALE: f32 = 4.0;
 
 /// Struct for rendering a custom node in the Topology section
 #[derive(Clone)]
 pub struct NodeShape {
+    id: NodeId,
     label: String,
     node_type: NodeType,
     loc: Pos2,
@@ -28,6 +39,7 @@ impl From<NodeProps<(NodeId, NodeType)>> for NodeShape {
         };
         label.push_str(&node_props.payload.0.to_string());
         Self {
+            id: node_props.payload.0,
             label,
             node_type: node_props.payload.1,
             loc: node_props.location(),
@@ -62,14 +74,84 @@ impl<E: Clone, Ty: EdgeType, Ix: IndexType> DisplayNode<(NodeId, NodeType), E, T
             -galley.size().y / 2.0 - radius * 2.0,
         );
 
+        // nodes without a value in the heatmap, like clients and servers
+        // when showing the drop ratio, keep their color
+        let color = HeatmapState::load_frame(ctx.ctx)
+            .and_then(|heatmap| heatmap.nodes.get(&self.id).copied())
+            .map_or(COLOR, heat_color);
+
         // create the shapes
         let mut res = match self.node_type {
-            NodeType::Client => NodeShape::get_client_shapes(center, radius),
-            NodeType::Drone => NodeShape::get_drone_shapes(center, radius),
-            NodeType::Server => NodeShape::get_server_shapes(center, radius),
+            NodeType::Client => NodeShape::get_client_shapes(center, radius, color),
+            NodeType::Drone => NodeShape::get_drone_shapes(center, radius, color),
+            NodeType::Server => NodeShape::get_server_shapes(center, radius, color),
         };
         let shape_label = TextShape::new(center + label_offset, galley, COLOR);
         res.push(Shape::from(shape_label));
+
+        // remember the node under the pointer, and whether it was clicked,
+        // for the topology section to show its tooltip and open its window
+        let (hover, clicked) = ctx
+            .ctx
+            .input(|i| (i.pointer.hover_pos(), i.pointer.primary_clicked()));
+        if hover.is_some_and(|pos| pos.distance(center) < radius * 1.3) {
+            ctx.ctx.data_mut(|d| {
+                d.insert_temp(hovered_id(), self.id);
+                if clicked {
+                    d.insert_temp(clicked_id(), self.id);
+                }
+            });
+        }
+
+        // drones selected for bulk actions, and nodes inside the lasso
+        if let Some(frame) = Selection::load_frame(ctx.ctx) {
+            if frame.drones.contains(&self.id) {
+                res.push(Shape::circle_stroke(
+                    center,
+                    radius * 2.3,
+                    Stroke::new(radius * 0.25, SELECTION_COLOR),
+                ));
+            }
+            if frame.lasso.is_some_and(|lasso| lasso.contains(center)) {
+                selection::mark_lassoed(ctx.ctx, self.id);
+            }
+        }
+
+        // endpoints of the highlighted paths
+        if let Some(paths) = PathOverlay::load_frame(ctx.ctx) {
+            if paths.endpoints.contains(&self.id) {
+                res.push(Shape::circle_stroke(
+                    center,
+                    radius * 1.8,
+                    Stroke::new(radius * 0.3, PATH_COLOR),
+                ));
+            }
+        }
+
+        // drones whose crash would break something are circled
+        if let Some(safety) = SafetyOverlay::load_frame(ctx.ctx) {
+            if safety.drones.contains(&self.id) {
+                res.push(Shape::circle_stroke(
+                    center,
+                    radius * 1.8,
+                    Stroke::new(radius * 0.3, UNSAFE_COLOR),
+                ));
+            }
+        }
+
+        // fragments dropped by the node
+        if let Some(frame) = AnimationState::load_frame(ctx.ctx) {
+            for progress in frame.bursts.get(&self.id).into_iter().flatten() {
+                let progress = progress.clamp(0.0, 1.0);
+                let burst_radius = radius * (1.0 + (BURST_SCALE - 1.0) * progress);
+                let color = BURST_COLOR.gamma_multiply(1.0 - progress);
+                res.push(Shape::circle_stroke(
+                    center,
+                    burst_radius,
+                    Stroke::new(radius * 0.4, color),
+                ));
+            }
+        }
         res
     }
 
@@ -82,22 +164,49 @@ impl<E: Clone, Ty: EdgeType, Ix: IndexType> DisplayNode<(NodeId, NodeType), E, T
     }
 }
 
+/// Returns the node under the pointer in the last frame, if any, and
+/// forgets it.
+pub fn take_hovered(ctx: &Context) -> Option<NodeId> {
+    take_temp(ctx, hovered_id())
+}
+
+/// Returns the node clicked in the last frame, if any, and forgets it.
+pub fn take_clicked(ctx: &Context) -> Option<NodeId> {
+    take_temp(ctx, clicked_id())
+}
+
+fn take_temp(ctx: &Context, id: Id) -> Option<NodeId> {
+    ctx.data_mut(|d| {
+        let node = d.get_temp(id);
+        d.remove::<NodeId>(id);
+        node
+    })
+}
+
+fn hovered_id() -> Id {
+    Id::new("node_hovered")
+}
+
+fn clicked_id() -> Id {
+    Id::new("node_clicked")
+}
+
 impl NodeShape {
     /// Returns a vector containing the shapes used for rendering a client node
-    fn get_client_shapes(screen_center: Pos2, screen_radius: f32) -> Vec<Shape> {
-        let shape_circle = Shape::circle_filled(screen_center, screen_radius, COLOR);
+    fn get_client_shapes(screen_center: Pos2, screen_radius: f32, color: Color32) -> Vec<Shape> {
+        let shape_circle = Shape::circle_filled(screen_center, screen_radius, color);
         vec![shape_circle]
     }
 
     /// Returns a vector containing the shapes used for rendering a server node
-    fn get_server_shapes(screen_center: Pos2, screen_radius: f32) -> Vec<Shape> {
+    fn get_server_shapes(screen_center: Pos2, screen_radius: f32, color: Color32) -> Vec<Shape> {
         let shape_rect = Shape::Rect(RectShape {
             rect: Rect::from_center_size(
                 screen_center,
                 Vec2::new(screen_radius * 2.0, screen_radius * 2.0),
             ),
             rounding: Rounding::same(screen_radius * 0.2),
-            fill: COLOR,
+            fill: color,
             stroke: Stroke::default(),
             blur_width: 0.0,
             fill_texture_id: TextureId::default(),
@@ -107,14 +216,14 @@ impl NodeShape {
     }
 
     /// Returns a vector containing the shapes used for rendering a drone node
-    fn get_drone_shapes(screen_center: Pos2, screen_radius: f32) -> Vec<Shape> {
+    fn get_drone_shapes(screen_center: Pos2, screen_radius: f32, color: Color32) -> Vec<Shape> {
         let shape_rect = Shape::Rect(RectShape {
             rect: Rect::from_center_size(
                 screen_center,
                 Vec2::new(screen_radius * 1.2, screen_radius * 1.2),
             ),
             rounding: Rounding::default(),
-            fill: COLOR,
+            fill: color,
             stroke: Stroke::default(),
             blur_width: 0.0,
             fill_texture_id: TextureId::default(),
@@ -125,7 +234,7 @@ impl NodeShape {
             for j in [-0.6, 0.6] {
                 let offset = Vec2::new(screen_radius * i, screen_radius * j);
                 let shape_circle =
-                    Shape::circle_filled(screen_center + offset, screen_radius * 0.4, COLOR);
+                    Shape::circle_filled(screen_center + offset, screen_radius * 0.4, color);
                 res.push(shape_circle);
             }
         }
@@ -1,13 +1,16 @@
 use std::sync::MutexGuard;
 
-use eframe::egui::{vec2, Color32, ComboBox, Context, Slider, Ui, Window};
+use eframe::egui::{vec2, ComboBox, Context, DragValue, Slider, Ui, Window};
 
+use crate::analysis::Fault;
 use crate::app::simulation_controller_ui::DroneWindowState;
-use crate::shared_data::SimulationData;
+use crate::pdr_schedule::PdrSchedule;
+use crate::shared_data::{LogEntry, LogKind, Severity, SimulationData};
 use crate::ui_components;
 use wg_2024::network::NodeId;
 
-/// Spawns the drone window.
+/// Spawns the drone window. Returns the crash or the link removal asked by
+/// the user, which must be confirmed before being applied.
 /// #Arguments
 /// `id` is the id of the drone whose window needs to be spawned.
 pub fn spawn(
@@ -17,17 +20,23 @@ pub fn spawn(
     node_ids: &[NodeId],
     open: &mut bool,
     state: &mut DroneWindowState,
-) {
+) -> Option<Fault> {
+    let mut fault = None;
     Window::new(format!("{} #{id}", state.name))
+        .id(ui_components::node_window_id(id))
         .open(open)
-        .fixed_size(vec2(400.0, 300.0))
+        .default_size(vec2(400.0, 300.0))
+        .resizable(true)
+        .vscroll(true)
         .show(ctx, |ui| {
             // ----- stats -----
             ui_components::stats::spawn_drone(ui, mutex, id);
             ui.add_space(5.0);
+            ui_components::charts::spawn_node(ui, mutex, id);
+            ui.add_space(5.0);
 
             // ----- logs -----
-            ui_components::logs::spawn(ui, mutex, id);
+            ui_components::logs::spawn(ui, mutex, id, &mut state.log_filter);
             ui.add_space(5.0);
 
             ui_components::text::spawn_white_heading(ui, "Actions");
@@ -42,6 +51,16 @@ pub fn spawn(
 
             ui.add_space(3.0);
 
+            ui.horizontal(|ui| {
+                ui.monospace("Remove link with:");
+                spawn_remove_link_combobox(ui, mutex, id, state);
+                if let Some(f) = spawn_remove_button(ui, mutex, id, state) {
+                    fault = Some(f);
+                }
+            });
+
+            ui.add_space(3.0);
+
             ui.horizontal(|ui| {
                 ui.monospace("PDR:");
                 spawn_pdr_slider(ui, mutex, id, state);
@@ -49,15 +68,20 @@ pub fn spawn(
 
             ui.add_space(3.0);
 
+            spawn_pdr_schedule(ui, mutex, id, state);
+
+            ui.add_space(3.0);
+
             ui.horizontal(|ui| {
-                if ui.button("Crash").clicked() && mutex.sc.crash_drone(id).is_err() {
-                    mutex.add_log(id, "Cannot crash".to_string(), Color32::LIGHT_RED);
+                if ui.button("Crash").clicked() {
+                    fault = Some(Fault::Crash(id));
                 }
                 if ui.button("Clear log").clicked() {
                     mutex.clear_log(id);
                 }
             });
         });
+    fault
 }
 
 /// Spawns the drop-down menu for choosing the node id to add a link with.
@@ -93,21 +117,79 @@ fn spawn_add_button(
     state: &mut DroneWindowState,
 ) {
     if ui.button("Add").clicked() {
-        let log_line = match state.add_link_selected_id {
-            None => "Error: id not selected".to_string(),
+        match state.add_link_selected_id {
+            None => mutex.add_log(
+                LogEntry::new(
+                    id,
+                    LogKind::ControllerAction,
+                    "Error: id not selected".to_string(),
+                )
+                .with_severity(Severity::Error),
+            ),
             Some(sid) => {
-                match mutex.sc.add_edge(id, sid) {
-                    Ok(()) => {
-                        // push log to other node as well
-                        mutex.add_log(sid, format!("Link added with node {id}"), Color32::WHITE);
-                        format!("Link added with node {sid}")
-                    }
-                    Err(e) => format!("Error in adding link with {sid}: {e:?}"),
-                }
+                _ = mutex.add_link(id, sid);
+            }
+        }
+    }
+}
+
+/// Spawns the drop-down menu for choosing the neighbor to remove the link with.
+fn spawn_remove_link_combobox(
+    ui: &mut Ui,
+    mutex: &MutexGuard<SimulationData>,
+    id: NodeId,
+    state: &mut DroneWindowState,
+) {
+    let mut neighbors: Vec<NodeId> = mutex.sc.get_topology().neighbors(id).collect();
+    neighbors.sort_unstable();
+    // the selected neighbor may have been disconnected in the meantime
+    if state
+        .remove_link_selected_id
+        .is_some_and(|sid| !neighbors.contains(&sid))
+    {
+        state.remove_link_selected_id = None;
+    }
+    ComboBox::from_id_salt("remove_link_combobox")
+        .width(50.0)
+        .selected_text(
+            state
+                .remove_link_selected_id
+                .map_or_else(|| "-".to_string(), |num| num.to_string()),
+        )
+        .show_ui(ui, |ui| {
+            for number in neighbors {
+                ui.selectable_value(
+                    &mut state.remove_link_selected_id,
+                    Some(number),
+                    number.to_string(),
+                );
             }
-        };
+        });
+}
 
-        mutex.add_log(id, log_line, Color32::WHITE);
+/// Spawns the button for removing a link. Returns the removal, if asked.
+fn spawn_remove_button(
+    ui: &mut Ui,
+    mutex: &mut MutexGuard<SimulationData>,
+    id: NodeId,
+    state: &mut DroneWindowState,
+) -> Option<Fault> {
+    if !ui.button("Remove").clicked() {
+        return None;
+    }
+    match state.remove_link_selected_id.take() {
+        None => {
+            mutex.add_log(
+                LogEntry::new(
+                    id,
+                    LogKind::ControllerAction,
+                    "Error: id not selected".to_string(),
+                )
+                .with_severity(Severity::Error),
+            );
+            None
+        }
+        Some(sid) => Some(Fault::RemoveLink(id, sid)),
     }
 }
 
@@ -118,12 +200,129 @@ fn spawn_pdr_slider(
     id: NodeId,
     state: &mut DroneWindowState,
 ) {
+    // follow the changes made by the scheduler, by undos and by other
+    // windows, but keep a value being edited until it is applied
+    if let Some(pdr) = mutex.sc.get_pdr(id) {
+        if state.pdr_synced != Some(pdr) {
+            state.pdr_synced = Some(pdr);
+            state.pdr_slider = pdr;
+        }
+    }
     let response = ui.add(Slider::new(&mut state.pdr_slider, 0.0..=1.0));
     if response.drag_stopped() || response.lost_focus() {
-        let log_line = match mutex.sc.set_pdr(id, state.pdr_slider) {
-            Ok(()) => format!("Changed PDR to {}", state.pdr_slider),
-            Err(e) => format!("Failed to change PDR: {e:?}"),
-        };
-        mutex.add_log(id, log_line, Color32::WHITE);
+        // a manual change replaces the schedule
+        _ = mutex.set_pdr(id, state.pdr_slider);
     }
 }
+
+/// Spawns the editor of the PDR schedule, with the preview of the edited
+/// schedule and of the running one.
+fn spawn_pdr_schedule(
+    ui: &mut Ui,
+    mutex: &mut MutexGuard<SimulationData>,
+    id: NodeId,
+    state: &mut DroneWindowState,
+) {
+    let schedule = &mut state.pdr_schedule;
+    ui.horizontal(|ui| {
+        ui.monospace("PDR schedule:");
+        ComboBox::from_id_salt("pdr_schedule_combobox")
+            .selected_text(schedule.name())
+            .show_ui(ui, |ui| {
+                for kind in PdrSchedule::KINDS {
+                    let selected = kind.name() == schedule.name();
+                    if ui.selectable_label(selected, kind.name()).clicked() && !selected {
+                        *schedule = kind;
+                    }
+                }
+            });
+    });
+    ui.horizontal_wrapped(|ui| match schedule {
+        PdrSchedule::Ramp { from, to, duration } => {
+            spawn_pdr_value(ui, "From", from);
+            spawn_pdr_value(ui, "To", to);
+            spawn_seconds_value(ui, "Duration", duration);
+        }
+        PdrSchedule::Steps {
+            from,
+            to,
+            steps,
+            duration,
+        } => {
+            spawn_pdr_value(ui, "From", from);
+            spawn_pdr_value(ui, "To", to);
+            ui.label("Steps");
+            ui.add(DragValue::new(steps).range(1..=100));
+            spawn_seconds_value(ui, "Duration", duration);
+        }
+        PdrSchedule::Sine {
+            center,
+            amplitude,
+            period,
+            duration,
+        } => {
+            spawn_pdr_value(ui, "Center", center);
+            spawn_pdr_value(ui, "Amplitude", amplitude);
+            spawn_seconds_value(ui, "Period", period);
+            spawn_seconds_value(ui, "Duration", duration);
+        }
+        PdrSchedule::RandomWalk {
+            min,
+            max,
+            step,
+            seed,
+            duration,
+        } => {
+            spawn_pdr_value(ui, "Min", min);
+            spawn_pdr_value(ui, "Max", max);
+            spawn_pdr_value(ui, "Step", step);
+            ui.label("Seed");
+            ui.add(DragValue::new(seed));
+            spawn_seconds_value(ui, "Duration", duration);
+        }
+    });
+
+    // the samples are only computed again when the schedule was edited
+    let edited = match &mut state.pdr_preview {
+        Some((previewed, samples)) if previewed == schedule => samples,
+        preview => &mut preview.insert((*schedule, schedule.samples())).1,
+    };
+    let now = mutex.elapsed();
+    let active = mutex.get_pdr_schedule(id);
+    let running = active.map(|a| a.schedule);
+    ui_components::charts::spawn_pdr_schedule(
+        ui,
+        id,
+        edited,
+        active.map(|a| (a.samples(), now.saturating_sub(a.start).as_secs_f64())),
+    );
+
+    ui.horizontal(|ui| {
+        if ui.button("Start").clicked() {
+            mutex.start_pdr_schedule(id, *schedule);
+        }
+        if let Some(running) = running {
+            if ui.button("Stop").clicked() {
+                mutex.stop_pdr_schedule(id);
+            }
+            ui.label(format!("Running: {running}"));
+        }
+    });
+}
+
+/// Spawns the field for a PDR parameter of the schedule.
+fn spawn_pdr_value(ui: &mut Ui, label: &str, value: &mut f32) {
+    ui.label(label);
+    ui.add(DragValue::new(value).range(0.0..=1.0).speed(0.01));
+}
+
+/// Spawns the field for a time parameter of the schedule, in seconds.
+fn spawn_seconds_value(ui: &mut Ui, label: &str, value: &mut f64) {
+    ui.label(label);
+    ui.add(
+        DragValue::new(value)
+            .range(0.1..=3600.0)
+            .speed(0.5)
+            .suffix(" s"),
+    );
+}
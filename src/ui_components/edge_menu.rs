@@ -0,0 +1,39 @@
+use eframe::egui::{Area, Context, Frame, Id, Order, Pos2};
+
+use wg_2024::network::NodeId;
+
+use crate::analysis::Fault;
+
+/// Menu opened by right-clicking an edge in the Topology section.
+#[derive(Debug)]
+pub struct EdgeMenu {
+    pub edge: (NodeId, NodeId),
+    /// screen position of the click.
+    pub pos: Pos2,
+}
+
+/// Spawns the menu of the right-clicked edge, if any. Clicking outside of
+/// the menu closes it. Returns the link removal asked by the user, which
+/// must be confirmed before being applied.
+pub fn spawn(ctx: &Context, menu: &mut Option<EdgeMenu>) -> Option<Fault> {
+    let Some(EdgeMenu { edge: (a, b), pos }) = *menu else {
+        return None;
+    };
+    let mut fault = None;
+    let response = Area::new(Id::new("edge_menu"))
+        .order(Order::Foreground)
+        .fixed_pos(pos)
+        .show(ctx, |ui| {
+            Frame::popup(ui.style()).show(ui, |ui| {
+                ui.monospace(format!("Link #{a} – #{b}"));
+                if ui.button("Remove link").clicked() {
+                    fault = Some(Fault::RemoveLink(a, b));
+                }
+            });
+        })
+        .response;
+    if fault.is_some() || response.clicked_elsewhere() {
+        *menu = None;
+    }
+    fault
+}
@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use eframe::egui::{vec2, Color32, Context, Id, RichText, ScrollArea, Ui, Window};
+
+use wg_2024::network::NodeId;
+
+use crate::analysis::{Fault, Impact};
+use crate::shared_data::SimulationData;
+use crate::ui_components::text::spawn_white_heading;
+
+/// Color of the drones and links whose failure would break something.
+pub const UNSAFE_COLOR: Color32 = Color32::from_rgb(255, 150, 40);
+
+/// A crash or a link removal waiting for the user to confirm it.
+#[derive(Debug)]
+pub struct PendingFault {
+    pub fault: Fault,
+    pub impact: Impact,
+    pub source: FaultSource,
+}
+
+/// What asked for a fault, and what to do once it is confirmed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultSource {
+    /// a crash or a link removal asked from a window or the topology.
+    User,
+    /// the next undo, which removes a link that was added.
+    Undo,
+    /// the next redo, which removes a link again.
+    Redo,
+}
+
+/// Highlighting of the unsafe drones and links in the Topology section.
+#[derive(Default, Debug)]
+pub struct SafetyOverlay {
+    pub enabled: bool,
+    /// unsafe drones and links of the topology they were found in.
+    cache: Option<(TopologyKey, Arc<SafetyFrame>)>,
+}
+
+/// Nodes and links of a topology, sorted, to tell when it changed.
+type TopologyKey = (Vec<NodeId>, Vec<(NodeId, NodeId)>);
+
+/// Drones and links highlighted in the current frame, read by the node and
+/// edge shapes.
+#[derive(Default, Debug)]
+pub struct SafetyFrame {
+    pub drones: HashSet<NodeId>,
+    /// links, with the smallest id first.
+    pub links: HashSet<(NodeId, NodeId)>,
+}
+
+impl SafetyOverlay {
+    /// Finds the unsafe drones and links of the current topology and stores
+    /// them for the shapes to read. Nothing is stored if the overlay is
+    /// disabled.
+    pub fn update(&mut self, ctx: &Context, data: &SimulationData) {
+        if !self.enabled {
+            ctx.data_mut(|d| d.remove::<Arc<SafetyFrame>>(Self::frame_id()));
+            return;
+        }
+        let frame = self.unsafe_faults(data);
+        ctx.data_mut(|d| d.insert_temp(Self::frame_id(), frame));
+    }
+
+    /// Returns the unsafe drones and links of the current topology. They
+    /// are only searched again when the topology changed since the last
+    /// call, since the search runs a visit for every drone and link.
+    pub fn unsafe_faults(&mut self, data: &SimulationData) -> Arc<SafetyFrame> {
+        let topology = data.sc.get_topology();
+        let mut nodes: Vec<NodeId> = topology.nodes().collect();
+        nodes.sort_unstable();
+        let mut links: Vec<(NodeId, NodeId)> = topology
+            .all_edges()
+            .map(|(a, b, ())| (a.min(b), a.max(b)))
+            .collect();
+        links.sort_unstable();
+        let key = (nodes, links);
+        if let Some((cached, frame)) = &self.cache {
+            if *cached == key {
+                return frame.clone();
+            }
+        }
+        let (drones, links) = data.unsafe_faults();
+        let frame = Arc::new(SafetyFrame { drones, links });
+        self.cache = Some((key, frame.clone()));
+        frame
+    }
+
+    /// Returns the frame stored by the last update, if the overlay is enabled.
+    pub fn load_frame(ctx: &Context) -> Option<Arc<SafetyFrame>> {
+        ctx.data(|d| d.get_temp(Self::frame_id()))
+    }
+
+    fn frame_id() -> Id {
+        Id::new("safety_frame")
+    }
+
+    /// Spawns the toggle of the overlay.
+    pub fn spawn_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.checkbox(&mut self.enabled, "Highlight unsafe faults");
+            if self.enabled {
+                ui.label(
+                    RichText::new("■ crashing this drone or removing this link breaks routes")
+                        .color(UNSAFE_COLOR),
+                );
+            }
+        });
+    }
+}
+
+/// Spawns the window asking to confirm a crash or a link removal, listing
+/// what it would break. Returns the fault and its source if the user
+/// confirmed it. The window is closed by both buttons.
+pub fn spawn_confirmation(
+    ctx: &Context,
+    pending: &mut Option<PendingFault>,
+) -> Option<(Fault, FaultSource)> {
+    let Some(PendingFault {
+        fault,
+        impact,
+        source,
+    }) = pending
+    else {
+        return None;
+    };
+    let mut confirmed = None;
+    let mut close = false;
+    let mut open = true;
+    Window::new("Confirm action")
+        .open(&mut open)
+        .collapsible(false)
+        .default_size(vec2(350.0, 200.0))
+        .show(ctx, |ui| {
+            match source {
+                FaultSource::User => ui.label(format!("Do you want to {fault}?")),
+                FaultSource::Undo => ui.label(format!("Undoing the last action would {fault}.")),
+                FaultSource::Redo => ui.label(format!("Redoing the last action would {fault}.")),
+            };
+            if let Fault::Crash(_) = fault {
+                ui.colored_label(UNSAFE_COLOR, "A crash is irreversible: it can't be undone.");
+            }
+            ui.add_space(5.0);
+            if impact.is_safe() {
+                ui.colored_label(
+                    Color32::LIGHT_GREEN,
+                    "Every client keeps reaching the same servers and no node is left without links.",
+                );
+            } else {
+                ScrollArea::vertical()
+                    .max_height(200.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        if !impact.unreachable.is_empty() {
+                            spawn_white_heading(ui, "Unreachable pairs");
+                            for (client, server) in &impact.unreachable {
+                                ui.colored_label(
+                                    Color32::LIGHT_RED,
+                                    format!("client #{client} could no longer reach server #{server}"),
+                                );
+                            }
+                            ui.add_space(5.0);
+                        }
+                        if !impact.isolated.is_empty() {
+                            spawn_white_heading(ui, "Isolated nodes");
+                            for id in &impact.isolated {
+                                ui.colored_label(
+                                    Color32::LIGHT_RED,
+                                    format!("node #{id} would be left without links"),
+                                );
+                            }
+                        }
+                    });
+            }
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                let action = match fault {
+                    Fault::Crash(_) => "Crash",
+                    Fault::RemoveLink(..) => "Remove link",
+                };
+                let text = if impact.is_safe() {
+                    action.to_string()
+                } else {
+                    format!("{action} anyway")
+                };
+                if ui.button(text).clicked() {
+                    confirmed = Some((*fault, *source));
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+    if close || !open {
+        *pending = None;
+    }
+    confirmed
+}
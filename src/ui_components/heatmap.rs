@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use eframe::egui::{vec2, Color32, ComboBox, Context, Id, Rect, Sense, Ui};
+
+use wg_2024::network::NodeId;
+
+use crate::shared_data::SimulationData;
+
+/// Colors of the heatmap gradient, from no traffic to the highest.
+const GRADIENT: [Color32; 3] = [
+    Color32::from_rgb(60, 90, 200),
+    Color32::from_rgb(240, 220, 60),
+    Color32::from_rgb(230, 50, 40),
+];
+const LEGEND_SIZE: [f32; 2] = [120.0, 10.0];
+
+/// Metric that drives the color of the nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NodeMetric {
+    /// fragments dropped by a drone over the fragments it received.
+    #[default]
+    DropRatio,
+    /// packets sent or forwarded recently, relative to the busiest node.
+    Load,
+}
+
+/// State of the heatmap in the Topology section.
+#[derive(Default, Debug)]
+pub struct HeatmapState {
+    pub enabled: bool,
+    pub node_metric: NodeMetric,
+    /// highest traffic and load of the last frame, shown in the legend.
+    max_edge_rate: f64,
+    max_node_rate: f64,
+}
+
+/// Intensity, between 0 and 1, of every edge and node in the current frame,
+/// read by the edge and node shapes.
+#[derive(Default, Debug)]
+pub struct HeatmapFrame {
+    /// edges, with the smallest id first.
+    pub edges: HashMap<(NodeId, NodeId), f32>,
+    pub nodes: HashMap<NodeId, f32>,
+}
+
+impl HeatmapState {
+    /// Computes the intensity of every edge and node from the traffic in the
+    /// shared data and stores it for the shapes to read. Nothing is stored
+    /// if the heatmap is disabled.
+    pub fn update(&mut self, ctx: &Context, data: &SimulationData) {
+        if !self.enabled {
+            ctx.data_mut(|d| d.remove::<Arc<HeatmapFrame>>(Self::frame_id()));
+            return;
+        }
+        let now = data.elapsed();
+        let mut frame = HeatmapFrame::default();
+
+        let edge_rates: Vec<_> = data
+            .edge_traffic
+            .iter()
+            .map(|(edge, traffic)| (*edge, traffic.rate(now)))
+            .collect();
+        self.max_edge_rate = edge_rates.iter().map(|(_, r)| *r).fold(0.0, f64::max);
+        for (edge, rate) in edge_rates {
+            frame
+                .edges
+                .insert(edge, normalize(rate, self.max_edge_rate));
+        }
+
+        let node_rates: Vec<_> = data
+            .node_traffic
+            .iter()
+            .map(|(id, traffic)| (*id, traffic.rate(now)))
+            .collect();
+        self.max_node_rate = node_rates.iter().map(|(_, r)| *r).fold(0.0, f64::max);
+        match self.node_metric {
+            NodeMetric::DropRatio => {
+                for (id, stats) in &data.drone_stats {
+                    let dropped = stats.fragments_dropped;
+                    let received = dropped + stats.packets_forwarded[0];
+                    frame
+                        .nodes
+                        .insert(*id, normalize(dropped as f64, received as f64));
+                }
+            }
+            NodeMetric::Load => {
+                for (id, rate) in node_rates {
+                    frame.nodes.insert(id, normalize(rate, self.max_node_rate));
+                }
+            }
+        }
+        ctx.data_mut(|d| d.insert_temp(Self::frame_id(), Arc::new(frame)));
+    }
+
+    /// Returns the frame stored by the last update, if the heatmap is enabled.
+    pub fn load_frame(ctx: &Context) -> Option<Arc<HeatmapFrame>> {
+        ctx.data(|d| d.get_temp(Self::frame_id()))
+    }
+
+    fn frame_id() -> Id {
+        Id::new("heatmap_frame")
+    }
+
+    /// Spawns the heatmap toggle, the metric selector and the legend.
+    pub fn spawn_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.checkbox(&mut self.enabled, "Heatmap");
+            if !self.enabled {
+                return;
+            }
+            ui.label("Nodes:");
+            ComboBox::from_id_salt("heatmap_node_metric")
+                .selected_text(self.node_metric.to_string())
+                .show_ui(ui, |ui| {
+                    for metric in [NodeMetric::DropRatio, NodeMetric::Load] {
+                        ui.selectable_value(&mut self.node_metric, metric, metric.to_string());
+                    }
+                });
+            ui.separator();
+            ui.label("Links: 0");
+            spawn_gradient(ui);
+            ui.label(format!("{:.1} packets/s", self.max_edge_rate));
+            ui.separator();
+            ui.label("Nodes: 0");
+            spawn_gradient(ui);
+            match self.node_metric {
+                NodeMetric::DropRatio => ui.label("100% dropped"),
+                NodeMetric::Load => ui.label(format!("{:.1} packets/s", self.max_node_rate)),
+            };
+        });
+    }
+}
+
+/// returns the color of an intensity between 0 and 1.
+pub fn heat_color(intensity: f32) -> Color32 {
+    let t = intensity.clamp(0.0, 1.0) * (GRADIENT.len() - 1) as f32;
+    let i = (t.floor() as usize).min(GRADIENT.len() - 2);
+    lerp_color(GRADIENT[i], GRADIENT[i + 1], t - i as f32)
+}
+
+fn lerp_color(a: Color32, b: Color32, t: f32) -> Color32 {
+    let channel = |x: u8, y: u8| (f32::from(x) + (f32::from(y) - f32::from(x)) * t).round() as u8;
+    Color32::from_rgb(
+        channel(a.r(), b.r()),
+        channel(a.g(), b.g()),
+        channel(a.b(), b.b()),
+    )
+}
+
+/// returns `value / max` as an intensity, or 0 if `max` is 0.
+fn normalize(value: f64, max: f64) -> f32 {
+    if max > 0.0 {
+        (value / max) as f32
+    } else {
+        0.0
+    }
+}
+
+/// spawns a bar painted with the heatmap gradient.
+fn spawn_gradient(ui: &mut Ui) {
+    let (rect, _) = ui.allocate_exact_size(LEGEND_SIZE.into(), Sense::hover());
+    let steps = 24;
+    let step_width = rect.width() / steps as f32;
+    for i in 0..steps {
+        let step = Rect::from_min_size(
+            rect.min + vec2(step_width * i as f32, 0.0),
+            vec2(step_width + 0.5, rect.height()),
+        );
+        let color = heat_color(i as f32 / (steps - 1) as f32);
+        ui.painter().rect_filled(step, 0.0, color);
+    }
+}
+
+impl Display for NodeMetric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeMetric::DropRatio => write!(f, "Drop ratio"),
+            NodeMetric::Load => write!(f, "Forwarding load"),
+        }
+    }
+}
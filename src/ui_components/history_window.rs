@@ -0,0 +1,89 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{vec2, Button, Color32, Context, Grid, RichText, ScrollArea, Window};
+
+use crate::shared_data::SimulationData;
+use crate::ui_components::fault_safety::UNSAFE_COLOR;
+
+/// An undo or a redo asked by the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryRequest {
+    Undo,
+    Redo,
+}
+
+/// Spawns the window listing the actions of the user that changed the
+/// network, with the buttons to undo and redo them. Returns the button
+/// clicked, if any.
+pub fn spawn(
+    ctx: &Context,
+    mutex: &MutexGuard<SimulationData>,
+    open: &mut bool,
+) -> Option<HistoryRequest> {
+    let mut request = None;
+    Window::new("History")
+        .open(open)
+        .default_size(vec2(450.0, 300.0))
+        .show(ctx, |ui| {
+            let history = mutex.get_history();
+            let next_undo = history.next_undo();
+            let next_redo = history.next_redo();
+            let undo_text = next_undo.map(|i| history.entries()[i].action.to_string());
+            let redo_text = next_redo.map(|i| history.entries()[i].action.to_string());
+
+            ui.horizontal(|ui| {
+                let undo = ui
+                    .add_enabled(undo_text.is_some(), Button::new("Undo"))
+                    .on_hover_text(undo_text.as_deref().unwrap_or_default())
+                    .on_disabled_hover_text("Nothing to undo");
+                if undo.clicked() {
+                    request = Some(HistoryRequest::Undo);
+                }
+                let redo = ui
+                    .add_enabled(redo_text.is_some(), Button::new("Redo"))
+                    .on_hover_text(redo_text.as_deref().unwrap_or_default())
+                    .on_disabled_hover_text("Nothing to redo");
+                if redo.clicked() {
+                    request = Some(HistoryRequest::Redo);
+                }
+                ui.label("ctrl + Z / ctrl + shift + Z");
+            });
+            ui.add_space(5.0);
+
+            let history = mutex.get_history();
+            if history.entries().is_empty() {
+                ui.label("No action yet.");
+                return;
+            }
+            ui.group(|ui| {
+                ScrollArea::vertical()
+                    .max_height(250.0)
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        Grid::new("history_grid").striped(true).show(ui, |ui| {
+                            for (i, entry) in history.entries().iter().enumerate() {
+                                ui.monospace(format!("{:>7.2}s", entry.time.as_secs_f64()));
+                                let mut text = RichText::new(entry.action.to_string());
+                                let status = if entry.action.inverse().is_none() {
+                                    RichText::new("irreversible").color(UNSAFE_COLOR)
+                                } else if entry.stale {
+                                    RichText::new("can't be undone").color(Color32::LIGHT_RED)
+                                } else if entry.undone {
+                                    text = text.strikethrough().weak();
+                                    RichText::new("undone").weak()
+                                } else if Some(i) == next_undo {
+                                    RichText::new("next undo").color(Color32::LIGHT_GREEN)
+                                } else {
+                                    RichText::new("")
+                                };
+                                ui.label(text);
+                                ui.label(status);
+                                ui.end_row();
+                            }
+                        });
+                    });
+            });
+        });
+    request
+}
@@ -0,0 +1,93 @@
+use eframe::egui::{vec2, Color32, ComboBox, Context, Grid, RichText, ScrollArea, Window};
+
+use crate::app::simulation_controller_ui::ImplementationsWindowState;
+use crate::cli::DroneMode;
+use crate::topology::DroneImplementation;
+use crate::ui_components::text::spawn_white_heading;
+
+/// Spawns the window for choosing the implementation of each drone before
+/// resetting. Returns true if the user asked to reset with the chosen drones.
+pub fn spawn(ctx: &Context, state: &mut ImplementationsWindowState) -> bool {
+    let mut reset = false;
+    let mut open = state.open;
+    Window::new("Choose drones")
+        .open(&mut open)
+        .default_size(vec2(350.0, 300.0))
+        .show(ctx, |ui| {
+            if let Some(e) = &state.error {
+                ui.label(
+                    RichText::new(e.to_string())
+                        .monospace()
+                        .color(Color32::LIGHT_RED),
+                );
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.monospace("Other drones:");
+                ui.radio_value(&mut state.drone_mode, DroneMode::Fair, "Fair drones");
+                ui.radio_value(
+                    &mut state.drone_mode,
+                    DroneMode::LockheedRustin,
+                    "Lockheed Rustin",
+                );
+            });
+            ui.add_space(5.0);
+
+            spawn_white_heading(ui, "Drones");
+            ui.add_space(5.0);
+            let implementations = DroneImplementation::all();
+            ScrollArea::vertical()
+                .max_height(250.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    Grid::new("implementations_grid")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for &id in &state.drone_ids {
+                                ui.monospace(format!("Drone #{id}"));
+                                let mut choice = state.choices.get(&id).cloned();
+                                ComboBox::from_id_salt(("implementation", id))
+                                    .selected_text(
+                                        choice.as_ref().map_or_else(
+                                            || "Default".to_string(),
+                                            |i| i.to_string(),
+                                        ),
+                                    )
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut choice, None, "Default");
+                                        for implementation in &implementations {
+                                            ui.selectable_value(
+                                                &mut choice,
+                                                Some(implementation.clone()),
+                                                implementation.to_string(),
+                                            );
+                                        }
+                                    });
+                                match choice {
+                                    Some(implementation) => {
+                                        state.choices.insert(id, implementation);
+                                    }
+                                    None => {
+                                        state.choices.remove(&id);
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Reset with these drones").clicked() {
+                    reset = true;
+                }
+                if ui.button("Use default for all").clicked() {
+                    state.choices.clear();
+                }
+            });
+        });
+    state.open = open && !reset;
+    reset
+}
@@ -1,26 +1,203 @@
 use std::sync::MutexGuard;
 
-use eframe::egui::{Color32, RichText, ScrollArea, Ui};
+use eframe::egui::text::LayoutJob;
+use eframe::egui::{
+    Color32, CursorIcon, RichText, ScrollArea, Sense, TextEdit, TextFormat, TextStyle, Ui,
+};
 
 use wg_2024::network::NodeId;
 
-use crate::shared_data::SimulationData;
+use crate::shared_data::{LogEntry, LogKind, PacketKind, SessionKey, Severity, SimulationData};
 use crate::ui_components::text::spawn_white_heading;
 
-/// spawns a scroll area containing logs for the node with given id
-pub fn spawn(ui: &mut Ui, mutex: &MutexGuard<SimulationData>, id: NodeId) {
+const HIGHLIGHT_COLOR: Color32 = Color32::from_rgb(100, 90, 20);
+
+/// State of the filter bar of a log view.
+#[derive(Debug)]
+pub struct LogFilter {
+    /// text that entries must contain, ignoring ASCII case.
+    pub text: String,
+    pub fragment: bool,
+    pub ack: bool,
+    pub nack: bool,
+    pub flood: bool,
+    pub controller: bool,
+    /// show only warnings and errors, like drops, nacks and failed actions.
+    pub only_errors: bool,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            fragment: true,
+            ack: true,
+            nack: true,
+            flood: false,
+            controller: true,
+            only_errors: false,
+        }
+    }
+}
+
+impl LogFilter {
+    /// returns true if the entry should be shown.
+    #[must_use]
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        if self.only_errors && entry.severity == Severity::Info {
+            return false;
+        }
+        let kind_shown = match entry.kind {
+            LogKind::PacketSent(kind) | LogKind::PacketReceived(kind) => match kind {
+                PacketKind::Fragment => self.fragment,
+                PacketKind::Ack => self.ack,
+                PacketKind::Nack => self.nack,
+                PacketKind::FloodRequest | PacketKind::FloodResponse => self.flood,
+            },
+            LogKind::ControllerAction => self.controller,
+            LogKind::PacketDropped
+            | LogKind::MessageAssembled
+            | LogKind::MessageFragmented
+            | LogKind::Error => true,
+        };
+        kind_shown
+            && (self.text.is_empty()
+                || entry
+                    .text
+                    .to_ascii_lowercase()
+                    .contains(&self.text.to_ascii_lowercase()))
+    }
+}
+
+/// spawns a scroll area containing logs for the node with given id, with a
+/// bar for filtering them. Messages whose session is known can be clicked:
+/// returns the session of the message clicked, if any.
+pub fn spawn(
+    ui: &mut Ui,
+    mutex: &MutexGuard<SimulationData>,
+    id: NodeId,
+    filter: &mut LogFilter,
+) -> Option<SessionKey> {
     spawn_white_heading(ui, "History");
     ui.add_space(5.0);
+    spawn_filter_bar(ui, filter);
+    ui.add_space(3.0);
+    let mut clicked_session = None;
     ui.group(|ui| {
         ScrollArea::vertical()
             .stick_to_bottom(true)
             .auto_shrink([false, false])
             .show(ui, |ui| {
-                for line in mutex.get_logs(id) {
-                    ui.label(colored_monospace_text(&line.0, line.1));
+                for entry in mutex.get_logs(id).filter(|e| filter.matches(e)) {
+                    let response = if filter.text.is_empty() {
+                        ui.label(colored_monospace_text(
+                            &format_entry(entry),
+                            entry_color(entry),
+                        ))
+                    } else {
+                        let job = highlighted_text(
+                            ui,
+                            &format_entry(entry),
+                            entry_color(entry),
+                            &filter.text,
+                        );
+                        ui.label(job)
+                    };
+                    if let Some(session @ (origin, session_id)) = traceable_session(entry) {
+                        let response = response
+                            .interact(Sense::click())
+                            .on_hover_cursor(CursorIcon::PointingHand)
+                            .on_hover_text(format!("Trace session {session_id} of #{origin}"));
+                        if response.clicked() {
+                            clicked_session = Some(session);
+                        }
+                    }
                 }
             });
     });
+    clicked_session
+}
+
+/// returns the session of the entry if it is a message that can be traced.
+fn traceable_session(entry: &LogEntry) -> Option<SessionKey> {
+    match entry.kind {
+        LogKind::MessageFragmented | LogKind::MessageAssembled => entry.session(),
+        _ => None,
+    }
+}
+
+/// spawns the search field and the toggles of a log filter.
+pub fn spawn_filter_bar(ui: &mut Ui, filter: &mut LogFilter) {
+    ui.horizontal(|ui| {
+        ui.label("Search:");
+        ui.add(
+            TextEdit::singleline(&mut filter.text)
+                .desired_width(120.0)
+                .font(TextStyle::Monospace),
+        );
+        if !filter.text.is_empty() && ui.small_button("x").clicked() {
+            filter.text.clear();
+        }
+    });
+    ui.horizontal_wrapped(|ui| {
+        ui.toggle_value(&mut filter.fragment, "Fragment");
+        ui.toggle_value(&mut filter.ack, "Ack");
+        ui.toggle_value(&mut filter.nack, "Nack");
+        ui.toggle_value(&mut filter.flood, "Flood");
+        ui.toggle_value(&mut filter.controller, "Controller");
+        ui.separator();
+        ui.toggle_value(&mut filter.only_errors, "Only errors");
+    });
+}
+
+/// returns a monospace text where every occurrence of `pattern`, ignoring
+/// ASCII case, has a highlighted background.
+pub fn highlighted_text(ui: &Ui, text: &str, color: Color32, pattern: &str) -> LayoutJob {
+    let font_id = TextStyle::Monospace.resolve(ui.style());
+    let normal = TextFormat::simple(font_id.clone(), color);
+    let highlighted = TextFormat {
+        background: HIGHLIGHT_COLOR,
+        ..TextFormat::simple(font_id, color)
+    };
+
+    let mut job = LayoutJob::default();
+    // ASCII lowercase keeps byte offsets unchanged
+    let lower_text = text.to_ascii_lowercase();
+    let lower_pattern = pattern.to_ascii_lowercase();
+    let mut last = 0;
+    for (start, _) in lower_text.match_indices(&lower_pattern) {
+        let end = start + lower_pattern.len();
+        job.append(&text[last..start], 0.0, normal.clone());
+        job.append(&text[start..end], 0.0, highlighted.clone());
+        last = end;
+    }
+    job.append(&text[last..], 0.0, normal);
+    job
+}
+
+/// returns the text of a log entry, prefixed by its time in seconds.
+pub fn format_entry(entry: &LogEntry) -> String {
+    format!("[{:.3}s] {}", entry.time.as_secs_f64(), entry.text)
+}
+
+/// returns the color used to render a log entry.
+pub fn entry_color(entry: &LogEntry) -> Color32 {
+    if entry.severity == Severity::Error {
+        return Color32::LIGHT_RED;
+    }
+    match entry.kind {
+        LogKind::PacketSent(kind) | LogKind::PacketReceived(kind) => match kind {
+            PacketKind::Ack => Color32::LIGHT_GREEN,
+            PacketKind::Nack => Color32::LIGHT_RED,
+            PacketKind::Fragment | PacketKind::FloodRequest | PacketKind::FloodResponse => {
+                Color32::GRAY
+            }
+        },
+        LogKind::PacketDropped | LogKind::Error => Color32::LIGHT_RED,
+        LogKind::MessageAssembled | LogKind::MessageFragmented | LogKind::ControllerAction => {
+            Color32::WHITE
+        }
+    }
 }
 
 fn colored_monospace_text(text: &String, color: Color32) -> RichText {
@@ -0,0 +1,79 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{Grid, Ui};
+
+use wg_2024::network::NodeId;
+
+use crate::shared_data::SimulationData;
+
+/// Spawns the key statistics of a node, shown when hovering it in the
+/// Topology section.
+pub fn spawn(ui: &mut Ui, mutex: &MutexGuard<SimulationData>, id: NodeId) {
+    let neighbors = mutex.sc.get_topology().neighbors(id).count();
+    if let Some(stats) = mutex.drone_stats.get(&id) {
+        let name = mutex.sc.get_group_name(id).unwrap_or_default();
+        ui.strong(format!("{name} #{id}"));
+        let forwarded = stats.packets_forwarded[0];
+        let received = forwarded + stats.fragments_dropped;
+        let drop_ratio = if received > 0 {
+            stats.fragments_dropped as f64 / received as f64 * 100.0
+        } else {
+            0.0
+        };
+        Grid::new("node_tooltip").show(ui, |ui| {
+            spawn_row(
+                ui,
+                "PDR",
+                mutex.sc.get_pdr(id).unwrap_or_default().to_string(),
+            );
+            spawn_row(ui, "Links", neighbors.to_string());
+            spawn_row(ui, "Fragments forwarded", forwarded.to_string());
+            spawn_row(ui, "Fragments dropped", stats.fragments_dropped.to_string());
+            spawn_row(ui, "Drop ratio", format!("{drop_ratio:.1}%"));
+        });
+    } else {
+        let (name, stats) = if let Some(stats) = mutex.client_stats.get(&id) {
+            (
+                "Client",
+                (
+                    stats.packets_sent,
+                    stats.packets_received,
+                    stats.messages_fragmented,
+                    stats.messages_assembled,
+                ),
+            )
+        } else if let Some(stats) = mutex.server_stats.get(&id) {
+            (
+                "Server",
+                (
+                    stats.packets_sent,
+                    stats.packets_received,
+                    stats.messages_fragmented,
+                    stats.messages_assembled,
+                ),
+            )
+        } else {
+            return;
+        };
+        let (sent, received, fragmented, assembled) = stats;
+        ui.strong(format!("{name} #{id}"));
+        Grid::new("node_tooltip").show(ui, |ui| {
+            spawn_row(ui, "Links", neighbors.to_string());
+            spawn_row(ui, "Packets sent", sent.iter().sum::<u64>().to_string());
+            spawn_row(
+                ui,
+                "Packets received",
+                received.iter().sum::<u64>().to_string(),
+            );
+            spawn_row(ui, "Messages fragmented", fragmented.to_string());
+            spawn_row(ui, "Messages assembled", assembled.to_string());
+        });
+    }
+    ui.label("Click to open its window");
+}
+
+fn spawn_row(ui: &mut Ui, name: &str, value: String) {
+    ui.label(name);
+    ui.monospace(value);
+    ui.end_row();
+}
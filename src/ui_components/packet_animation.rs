@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use eframe::egui::{Color32, Context, Id, RichText, Slider, Ui};
+
+use wg_2024::network::NodeId;
+
+use crate::shared_data::{Movement, MovementKind, PacketKind};
+
+/// Time a packet takes to cross an edge at speed 1, in seconds.
+const EDGE_CROSSING_TIME: f64 = 0.6;
+/// Duration of the burst shown when a drone drops a fragment, in seconds.
+const BURST_TIME: f64 = 0.5;
+/// Movements older than this when the ui sees them are not animated, for
+/// example the ones recorded while another section was shown.
+const MAX_MOVEMENT_AGE: Duration = Duration::from_secs(1);
+
+/// Packet types in the order used by the legend.
+const PACKET_KINDS: [(PacketKind, &str); 5] = [
+    (PacketKind::Fragment, "Fragment"),
+    (PacketKind::Ack, "Ack"),
+    (PacketKind::Nack, "Nack"),
+    (PacketKind::FloodRequest, "Flood Req."),
+    (PacketKind::FloodResponse, "Flood Resp."),
+];
+
+/// A packet travelling along an edge.
+#[derive(Debug)]
+struct Dot {
+    from: NodeId,
+    to: NodeId,
+    packet: PacketKind,
+    /// ui time when the packet left `from`.
+    start: f64,
+}
+
+/// State of the packet animation in the Topology section.
+#[derive(Debug)]
+pub struct AnimationState {
+    pub enabled: bool,
+    /// multiplier of the speed of the packets.
+    pub speed: f64,
+    /// maximum number of packets on screen, the others are skipped.
+    pub max_dots: usize,
+    dots: Vec<Dot>,
+    /// drones that dropped a fragment, with the ui time of the drop.
+    bursts: Vec<(NodeId, f64)>,
+    /// packets that were not animated because too many were on screen.
+    skipped: u64,
+}
+
+/// Position of every packet on screen in the current frame, read by the
+/// edge and node shapes.
+#[derive(Default, Debug)]
+pub struct AnimationFrame {
+    /// progress, between 0 and 1, and color of the packets travelling from
+    /// the first node to the second.
+    pub dots: HashMap<(NodeId, NodeId), Vec<(f32, Color32)>>,
+    /// progress, between 0 and 1, of the drop bursts of each drone.
+    pub bursts: HashMap<NodeId, Vec<f32>>,
+}
+
+impl Default for AnimationState {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            speed: 1.0,
+            max_dots: 300,
+            dots: vec![],
+            bursts: vec![],
+            skipped: 0,
+        }
+    }
+}
+
+impl AnimationState {
+    /// Starts animating the movements recorded since the last frame, drops
+    /// the animations that ended and stores the current frame for the
+    /// shapes to read. `elapsed` is the current time of the simulation.
+    pub fn update(&mut self, ctx: &Context, movements: Vec<Movement>, elapsed: Duration) {
+        let now = ctx.input(|i| i.time);
+        let crossing_time = EDGE_CROSSING_TIME / self.speed;
+        self.dots.retain(|d| now - d.start < crossing_time);
+        self.bursts.retain(|(_, start)| now - start < BURST_TIME);
+
+        if self.enabled {
+            let recent = movements
+                .into_iter()
+                .filter(|m| elapsed.saturating_sub(m.time) <= MAX_MOVEMENT_AGE);
+            for m in recent {
+                match m.kind {
+                    MovementKind::Sent { from, to, packet } => {
+                        if self.dots.len() < self.max_dots {
+                            self.dots.push(Dot {
+                                from,
+                                to,
+                                packet,
+                                start: now,
+                            });
+                        } else {
+                            self.skipped += 1;
+                        }
+                    }
+                    MovementKind::Dropped { at } => self.bursts.push((at, now)),
+                }
+            }
+        } else {
+            self.dots.clear();
+            self.bursts.clear();
+        }
+
+        let mut frame = AnimationFrame::default();
+        for d in &self.dots {
+            let progress = ((now - d.start) / crossing_time) as f32;
+            frame
+                .dots
+                .entry((d.from, d.to))
+                .or_default()
+                .push((progress, packet_color(d.packet)));
+        }
+        for (id, start) in &self.bursts {
+            let progress = ((now - start) / BURST_TIME) as f32;
+            frame.bursts.entry(*id).or_default().push(progress);
+        }
+        ctx.data_mut(|d| d.insert_temp(Self::frame_id(), Arc::new(frame)));
+
+        if !self.dots.is_empty() || !self.bursts.is_empty() {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Returns the frame stored by the last update, if any.
+    pub fn load_frame(ctx: &Context) -> Option<Arc<AnimationFrame>> {
+        ctx.data(|d| d.get_temp(Self::frame_id()))
+    }
+
+    fn frame_id() -> Id {
+        Id::new("packet_animation_frame")
+    }
+
+    /// Spawns the controls of the animation and the legend of the colors.
+    pub fn spawn_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.checkbox(&mut self.enabled, "Animate packets");
+            ui.add_enabled(
+                self.enabled,
+                Slider::new(&mut self.speed, 0.25..=4.0)
+                    .logarithmic(true)
+                    .text("speed"),
+            );
+            ui.add_enabled(
+                self.enabled,
+                Slider::new(&mut self.max_dots, 10..=2000)
+                    .logarithmic(true)
+                    .text("max packets on screen"),
+            );
+            ui.monospace(format!(
+                "On screen: {}   Skipped: {}",
+                self.dots.len(),
+                self.skipped
+            ));
+            ui.separator();
+            for (kind, name) in PACKET_KINDS {
+                ui.label(RichText::new(format!("● {name}")).color(packet_color(kind)));
+            }
+            ui.label(RichText::new("◯ Drop").color(Color32::RED));
+        });
+    }
+}
+
+/// returns the color of the dots of a packet type.
+pub fn packet_color(packet: PacketKind) -> Color32 {
+    match packet {
+        PacketKind::Fragment => Color32::LIGHT_BLUE,
+        PacketKind::Ack => Color32::LIGHT_GREEN,
+        PacketKind::Nack => Color32::from_rgb(255, 140, 60),
+        PacketKind::FloodRequest => Color32::LIGHT_YELLOW,
+        PacketKind::FloodResponse => Color32::from_rgb(200, 150, 255),
+    }
+}
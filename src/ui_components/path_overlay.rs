@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use eframe::egui::{Color32, ComboBox, Context, Id, RichText, ScrollArea, Ui};
+
+use wg_2024::network::NodeId;
+
+use crate::analysis::PathMode;
+use crate::shared_data::SimulationData;
+
+/// Color of the highlighted paths.
+pub const PATH_COLOR: Color32 = Color32::from_rgb(80, 220, 230);
+/// Maximum number of paths searched, to keep dense topologies responsive.
+const MAX_PATHS: usize = 500;
+const LIST_HEIGHT: f32 = 90.0;
+
+/// Paths between a client and a server highlighted in the Topology section.
+#[derive(Default, Debug)]
+pub struct PathOverlay {
+    pub enabled: bool,
+    pub client: Option<NodeId>,
+    pub server: Option<NodeId>,
+    pub mode: PathMode,
+    /// index of the path highlighted alone, if any.
+    pub selected: Option<usize>,
+    /// paths of the last update, with their delivery probability.
+    paths: Vec<(Vec<NodeId>, f64)>,
+    truncated: bool,
+}
+
+/// Edges and nodes of the highlighted paths in the current frame, read by
+/// the edge and node shapes.
+#[derive(Default, Debug)]
+pub struct PathFrame {
+    /// edges, with the smallest id first.
+    pub edges: HashSet<(NodeId, NodeId)>,
+    pub endpoints: HashSet<NodeId>,
+}
+
+impl PathOverlay {
+    /// Searches the paths in the current topology and stores their edges
+    /// for the shapes to read. Nothing is stored if the overlay is disabled
+    /// or the endpoints are not chosen.
+    pub fn update(&mut self, ctx: &Context, data: &SimulationData) {
+        let (Some(client), Some(server), true) = (self.client, self.server, self.enabled) else {
+            self.paths.clear();
+            ctx.data_mut(|d| d.remove::<Arc<PathFrame>>(Self::frame_id()));
+            return;
+        };
+        (self.paths, self.truncated) = data.paths(client, server, self.mode, MAX_PATHS);
+        if self.selected.is_some_and(|i| i >= self.paths.len()) {
+            self.selected = None;
+        }
+
+        let mut frame = PathFrame {
+            endpoints: HashSet::from([client, server]),
+            ..PathFrame::default()
+        };
+        let highlighted = self
+            .paths
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.selected.is_none() || self.selected == Some(*i));
+        for (_, (path, _)) in highlighted {
+            for hop in path.windows(2) {
+                frame.edges.insert((hop[0].min(hop[1]), hop[0].max(hop[1])));
+            }
+        }
+        ctx.data_mut(|d| d.insert_temp(Self::frame_id(), Arc::new(frame)));
+    }
+
+    /// Returns the frame stored by the last update, if any.
+    pub fn load_frame(ctx: &Context) -> Option<Arc<PathFrame>> {
+        ctx.data(|d| d.get_temp(Self::frame_id()))
+    }
+
+    fn frame_id() -> Id {
+        Id::new("path_frame")
+    }
+
+    /// Spawns the endpoint selectors and the list of paths. Clicking a path
+    /// highlights it alone, clicking it again highlights all of them.
+    pub fn spawn_controls(&mut self, ui: &mut Ui, clients: &[NodeId], servers: &[NodeId]) {
+        ui.horizontal_wrapped(|ui| {
+            ui.checkbox(&mut self.enabled, "Paths");
+            if !self.enabled {
+                return;
+            }
+            spawn_endpoint_combobox(ui, "path_client", "Client", clients, &mut self.client);
+            ui.label("→");
+            spawn_endpoint_combobox(ui, "path_server", "Server", servers, &mut self.server);
+            ComboBox::from_id_salt("path_mode")
+                .selected_text(self.mode.to_string())
+                .show_ui(ui, |ui| {
+                    for mode in [PathMode::Shortest, PathMode::AllSimple] {
+                        ui.selectable_value(&mut self.mode, mode, mode.to_string());
+                    }
+                });
+            if self.client.is_some() && self.server.is_some() {
+                let text = if self.truncated {
+                    format!("first {} paths", self.paths.len())
+                } else {
+                    format!("{} paths", self.paths.len())
+                };
+                ui.label(RichText::new(text).color(PATH_COLOR));
+            }
+        });
+        if !self.enabled || self.paths.is_empty() {
+            return;
+        }
+        ScrollArea::vertical()
+            .id_salt("path_list")
+            .max_height(LIST_HEIGHT)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for (i, (path, probability)) in self.paths.iter().enumerate() {
+                    let route = path
+                        .iter()
+                        .map(|id| format!("#{id}"))
+                        .collect::<Vec<_>>()
+                        .join(" → ");
+                    let text = format!(
+                        "{route}   {} hops   delivery {:.1}%",
+                        path.len() - 1,
+                        probability * 100.0
+                    );
+                    let selected = self.selected == Some(i);
+                    if ui
+                        .selectable_label(selected, RichText::new(text).monospace())
+                        .clicked()
+                    {
+                        self.selected = if selected { None } else { Some(i) };
+                    }
+                }
+            });
+    }
+}
+
+/// spawns the drop-down menu for choosing an endpoint of the paths.
+fn spawn_endpoint_combobox(
+    ui: &mut Ui,
+    id_salt: &str,
+    name: &str,
+    ids: &[NodeId],
+    selected: &mut Option<NodeId>,
+) {
+    ComboBox::from_id_salt(id_salt)
+        .selected_text(selected.map_or_else(|| "-".to_string(), |id| format!("{name} #{id}")))
+        .show_ui(ui, |ui| {
+            for id in ids {
+                ui.selectable_value(selected, Some(*id), format!("{name} #{id}"));
+            }
+        });
+}
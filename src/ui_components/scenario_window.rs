@@ -0,0 +1,68 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{vec2, Color32, Context, RichText, ScrollArea, Window};
+
+use crate::app::simulation_controller_ui::ScenarioState;
+use crate::shared_data::SimulationData;
+use crate::ui_components::text::spawn_white_heading;
+
+/// Spawns the window showing the progress of the last scenario.
+/// Returns true if the user asked to run the scenario again.
+pub fn spawn(ctx: &Context, mutex: &MutexGuard<SimulationData>, state: &mut ScenarioState) -> bool {
+    let mut run_again = false;
+    let file_name = state.path.file_name().map_or_else(
+        || state.path.display().to_string(),
+        |n| n.to_string_lossy().to_string(),
+    );
+    Window::new(format!("Scenario {file_name}"))
+        .open(&mut state.window_open)
+        .default_size(vec2(450.0, 300.0))
+        .show(ctx, |ui| {
+            if let Some(e) = &state.error {
+                ui.label(
+                    RichText::new(e.to_string())
+                        .monospace()
+                        .color(Color32::LIGHT_RED),
+                );
+                return;
+            }
+
+            let reports = &mutex.scenario_reports;
+            let failed = reports.iter().filter(|r| r.result.is_err()).count();
+            ui.monospace(format!(
+                "Executed steps: {}/{}   Failed: {failed}",
+                reports.len(),
+                state.total_steps
+            ));
+            ui.add_space(5.0);
+
+            spawn_white_heading(ui, "Steps");
+            ui.add_space(5.0);
+            ui.group(|ui| {
+                ScrollArea::vertical()
+                    .max_height(200.0)
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for report in reports {
+                            let (line, color) = match &report.result {
+                                Ok(()) => (
+                                    format!("{:>7.2}s {}", report.at, report.description),
+                                    Color32::LIGHT_GREEN,
+                                ),
+                                Err(e) => (
+                                    format!("{:>7.2}s {}: {e}", report.at, report.description),
+                                    Color32::LIGHT_RED,
+                                ),
+                            };
+                            ui.label(RichText::new(line).monospace().color(color));
+                        }
+                    });
+            });
+            ui.add_space(5.0);
+            if ui.button("Run again").clicked() {
+                run_again = true;
+            }
+        });
+    run_again
+}
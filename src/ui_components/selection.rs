@@ -0,0 +1,103 @@
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+
+use eframe::egui::{Color32, Context, Id, Pos2, Rect, Stroke, Ui};
+
+use wg_2024::network::NodeId;
+
+/// Color of the selected drones and of the lasso.
+pub const SELECTION_COLOR: Color32 = Color32::from_rgb(90, 170, 255);
+/// lassos smaller than this, in screen points, are clicks.
+const MIN_LASSO_SIZE: f32 = 4.0;
+
+/// Drones selected for bulk actions, in the sidebar or in the Topology
+/// section.
+#[derive(Default, Debug)]
+pub struct Selection {
+    pub drones: BTreeSet<NodeId>,
+    /// where the lasso being drawn started.
+    pub lasso_start: Option<Pos2>,
+}
+
+/// Selected drones and lasso of the current frame, read by the node shapes.
+#[derive(Default, Debug)]
+pub struct SelectionFrame {
+    pub drones: HashSet<NodeId>,
+    pub lasso: Option<Rect>,
+}
+
+impl Selection {
+    /// Selects the drone if it isn't selected, deselects it otherwise.
+    pub fn toggle(&mut self, id: NodeId) {
+        if !self.drones.remove(&id) {
+            self.drones.insert(id);
+        }
+    }
+
+    /// Returns the lasso from where it started to the pointer, if one is
+    /// being drawn.
+    #[must_use]
+    pub fn lasso(&self, pointer: Option<Pos2>) -> Option<Rect> {
+        self.lasso_start
+            .zip(pointer)
+            .map(|(start, end)| Rect::from_two_pos(start, end))
+    }
+
+    /// Stores the selected drones and the lasso for the shapes to read.
+    pub fn update(&self, ctx: &Context, lasso: Option<Rect>) {
+        let frame = SelectionFrame {
+            drones: self.drones.iter().copied().collect(),
+            lasso,
+        };
+        ctx.data_mut(|d| d.insert_temp(Self::frame_id(), Arc::new(frame)));
+    }
+
+    /// Returns the frame stored by the last update.
+    pub fn load_frame(ctx: &Context) -> Option<Arc<SelectionFrame>> {
+        ctx.data(|d| d.get_temp(Self::frame_id()))
+    }
+
+    fn frame_id() -> Id {
+        Id::new("selection_frame")
+    }
+
+    /// Finishes the lasso, selecting the given drones unless it was too
+    /// small to be more than a click.
+    pub fn finish_lasso(&mut self, lasso: Rect, drones: impl IntoIterator<Item = NodeId>) {
+        self.lasso_start = None;
+        if lasso.width() >= MIN_LASSO_SIZE || lasso.height() >= MIN_LASSO_SIZE {
+            self.drones.extend(drones);
+        }
+    }
+}
+
+/// Draws the lasso being dragged.
+pub fn paint_lasso(ui: &Ui, lasso: Rect) {
+    ui.painter().rect(
+        lasso,
+        0.0,
+        SELECTION_COLOR.gamma_multiply(0.1),
+        Stroke::new(1.0, SELECTION_COLOR),
+    );
+}
+
+/// Records that a node is inside the lasso of the current frame.
+pub fn mark_lassoed(ctx: &Context, id: NodeId) {
+    ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<Vec<NodeId>>(lassoed_id())
+            .push(id);
+    });
+}
+
+/// Returns the nodes inside the lasso in the last frame, and forgets them.
+pub fn take_lassoed(ctx: &Context) -> Vec<NodeId> {
+    ctx.data_mut(|d| {
+        let nodes = d.get_temp(lassoed_id()).unwrap_or_default();
+        d.remove::<Vec<NodeId>>(lassoed_id());
+        nodes
+    })
+}
+
+fn lassoed_id() -> Id {
+    Id::new("nodes_lassoed")
+}
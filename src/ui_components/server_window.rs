@@ -1,27 +1,172 @@
 use std::sync::MutexGuard;
 
-use eframe::egui::{vec2, Context, Window};
+use eframe::egui::{vec2, ComboBox, Context, Grid, Ui, Window};
 
 use wg_2024::network::NodeId;
 
-use crate::shared_data::SimulationData;
+use crate::analysis::Fault;
+use crate::app::simulation_controller_ui::ServerWindowState;
+use crate::shared_data::{LogEntry, LogKind, Severity, SimulationData};
 use crate::ui_components;
 
-/// Spawns the server window.
-pub fn spawn(ctx: &Context, mutex: &mut MutexGuard<SimulationData>, open: &mut bool, id: NodeId) {
+/// Spawns the server window. Returns the link removal asked by the user,
+/// which must be confirmed before being applied.
+/// #Arguments
+/// `drone_ids` are the drones the server can be linked to.
+pub fn spawn(
+    ctx: &Context,
+    mutex: &mut MutexGuard<SimulationData>,
+    open: &mut bool,
+    id: NodeId,
+    drone_ids: &[NodeId],
+    state: &mut ServerWindowState,
+) -> Option<Fault> {
+    let mut fault = None;
     Window::new(format!("Server #{id}"))
+        .id(ui_components::node_window_id(id))
         .open(open)
-        .fixed_size(vec2(400.0, 300.0))
+        .default_size(vec2(400.0, 300.0))
+        .resizable(true)
+        .vscroll(true)
         .show(ctx, |ui| {
             ui_components::stats::spawn_server(ui, mutex, id);
+            ui_components::charts::spawn_node(ui, mutex, id);
+            ui.separator();
+            spawn_server_info(ui, mutex, id);
+            ui.separator();
             // logs
-            ui_components::logs::spawn(ui, mutex, id);
+            if let Some(session) = ui_components::logs::spawn(ui, mutex, id, &mut state.log_filter)
+            {
+                state.traced_session = Some(session);
+            }
 
             ui_components::text::spawn_white_heading(ui, "Actions");
             ui.add_space(5.0);
 
+            ui.horizontal(|ui| {
+                ui.monospace("Add link with:");
+                let mut candidates: Vec<NodeId> = drone_ids
+                    .iter()
+                    .copied()
+                    .filter(|&d| !mutex.sc.get_topology().contains_edge(id, d))
+                    .collect();
+                candidates.sort_unstable();
+                spawn_drone_combobox(
+                    ui,
+                    "add_link_combobox",
+                    &candidates,
+                    &mut state.add_link_selected_id,
+                );
+                if ui.button("Add").clicked() {
+                    match state.add_link_selected_id.take() {
+                        None => log_not_selected(mutex, id),
+                        Some(sid) => {
+                            _ = mutex.add_link(id, sid);
+                        }
+                    }
+                }
+            });
+
+            ui.add_space(3.0);
+
+            ui.horizontal(|ui| {
+                ui.monospace("Remove link with:");
+                let mut neighbors: Vec<NodeId> = mutex.sc.get_topology().neighbors(id).collect();
+                neighbors.sort_unstable();
+                spawn_drone_combobox(
+                    ui,
+                    "remove_link_combobox",
+                    &neighbors,
+                    &mut state.remove_link_selected_id,
+                );
+                if ui.button("Remove").clicked() {
+                    match state.remove_link_selected_id.take() {
+                        None => log_not_selected(mutex, id),
+                        Some(sid) => fault = Some(Fault::RemoveLink(id, sid)),
+                    }
+                }
+            });
+
+            ui.add_space(3.0);
+
             if ui.button("Clear log").clicked() {
                 mutex.clear_log(id);
             }
         });
+    ui_components::trace_window::spawn(ctx, mutex, id, &mut state.traced_session);
+    fault
+}
+
+/// Spawns what the server told its clients: its type, its files and the
+/// clients registered to its chat.
+fn spawn_server_info(ui: &mut Ui, mutex: &MutexGuard<SimulationData>, id: NodeId) {
+    let Some(stats) = mutex.server_stats.get(&id) else {
+        return;
+    };
+    ui_components::text::spawn_white_heading(ui, "Server");
+    Grid::new("server_info").num_columns(2).show(ui, |ui| {
+        ui.monospace("Type:");
+        ui.label(
+            stats
+                .server_type
+                .as_deref()
+                .unwrap_or("unknown, no client asked yet"),
+        );
+        ui.end_row();
+
+        ui.monospace("Files:");
+        match &stats.files {
+            None => ui.label("unknown, no client asked yet"),
+            Some(files) if files.is_empty() => ui.label("none"),
+            Some(files) => ui.label(files.join("\n")),
+        };
+        ui.end_row();
+
+        ui.monospace("Chat clients:");
+        if stats.chat_clients.is_empty() {
+            ui.label("none registered");
+        } else {
+            let clients = stats
+                .chat_clients
+                .iter()
+                .map(|c| format!("#{c}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            ui.label(clients);
+        }
+        ui.end_row();
+    });
+}
+
+/// Spawns the drop-down menu for choosing a drone among the given ones.
+fn spawn_drone_combobox(
+    ui: &mut Ui,
+    id_salt: &str,
+    drone_ids: &[NodeId],
+    selected: &mut Option<NodeId>,
+) {
+    // the selected drone may have been linked, unlinked or crashed
+    if selected.is_some_and(|sid| !drone_ids.contains(&sid)) {
+        *selected = None;
+    }
+    ComboBox::from_id_salt(id_salt)
+        .width(50.0)
+        .selected_text(selected.map_or_else(|| "-".to_string(), |num| num.to_string()))
+        .show_ui(ui, |ui| {
+            for &number in drone_ids {
+                ui.selectable_value(selected, Some(number), number.to_string());
+            }
+        });
+}
+
+/// Logs that an action was asked without choosing a drone.
+fn log_not_selected(mutex: &mut MutexGuard<SimulationData>, id: NodeId) {
+    mutex.add_log(
+        LogEntry::new(
+            id,
+            LogKind::ControllerAction,
+            "Error: id not selected".to_string(),
+        )
+        .with_severity(Severity::Error),
+    );
 }
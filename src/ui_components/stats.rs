@@ -4,7 +4,7 @@ use eframe::egui::{Direction, Grid, Layout, Ui};
 
 use wg_2024::network::NodeId;
 
-use crate::shared_data::SimulationData;
+use crate::shared_data::{SimulationData, PACKET_TYPES};
 use crate::ui_components::text::spawn_white_heading;
 
 /// spawns stats section for drone with given id
@@ -147,14 +147,7 @@ pub fn spawn_server(ui: &mut Ui, mutex: &MutexGuard<SimulationData>, id: NodeId)
 }
 
 fn spawn_packet_stats_table_header(ui: &mut Ui) {
-    for header in [
-        "Packet type",
-        "Fragment",
-        "Ack",
-        "Nack",
-        "Flood Req.",
-        "Flood Resp.",
-    ] {
+    for header in std::iter::once("Packet type").chain(PACKET_TYPES) {
         ui.with_layout(
             Layout::centered_and_justified(Direction::LeftToRight),
             |ui| {
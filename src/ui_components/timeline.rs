@@ -0,0 +1,171 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{
+    vec2, Align, Color32, ComboBox, Label, Layout, RichText, ScrollArea, TextEdit, TextStyle, Ui,
+};
+
+use wg_2024::network::NodeId;
+
+use crate::shared_data::{LogEntry, SessionKey, SimulationData};
+use crate::ui_components::logs::entry_color;
+use crate::ui_components::text::spawn_white_heading;
+
+/// widths of the time, node, node type, event and session columns. The hops
+/// column takes the remaining space.
+const COLUMN_WIDTHS: [f32; 5] = [90.0, 50.0, 60.0, 380.0, 150.0];
+
+/// State of the filters of the timeline section.
+#[derive(Default, Debug)]
+pub struct TimelineState {
+    /// node whose events are shown, all nodes if none.
+    pub node: Option<NodeId>,
+    /// session whose events are shown, typed by the user as `origin/id`.
+    pub session: String,
+}
+
+impl TimelineState {
+    /// returns true if the entry should be shown. An unparsable session
+    /// filter matches nothing.
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if self.node.is_some_and(|id| id != entry.node) {
+            return false;
+        }
+        self.session.trim().is_empty()
+            || parse_session(&self.session).is_some_and(|s| entry.session() == Some(s))
+    }
+}
+
+/// parses a session typed as `origin/id`, like the session column shows it.
+fn parse_session(s: &str) -> Option<SessionKey> {
+    let (origin, session_id) = s.trim().split_once('/')?;
+    Some((origin.trim().parse().ok()?, session_id.trim().parse().ok()?))
+}
+
+/// spawns the table with the events of all nodes, in chronological order.
+/// Only the visible rows are laid out.
+pub fn spawn(
+    ui: &mut Ui,
+    mutex: &MutexGuard<SimulationData>,
+    sorted_node_ids: &[NodeId],
+    state: &mut TimelineState,
+) {
+    spawn_white_heading(ui, "Timeline");
+    ui.add_space(5.0);
+    spawn_filter_bar(ui, sorted_node_ids, state);
+    ui.add_space(3.0);
+
+    let events: Vec<&LogEntry> = mutex.get_events().filter(|e| state.matches(e)).collect();
+    ui.monospace(format!(
+        "{} of {} events",
+        events.len(),
+        mutex.get_events().len()
+    ));
+    if mutex.evicted_events() > 0 {
+        ui.monospace(format!(
+            "{} older events were discarded, the timeline is full",
+            mutex.evicted_events()
+        ));
+    }
+    ui.add_space(3.0);
+
+    let row_height = ui.text_style_height(&TextStyle::Monospace);
+    ui.group(|ui| {
+        spawn_row(
+            ui,
+            row_height,
+            ["Time", "Node", "Type", "Event", "Session", "Hops"].map(String::from),
+            Color32::WHITE,
+        );
+        ui.separator();
+        ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, events.len(), |ui, range| {
+                for entry in &events[range] {
+                    spawn_row(
+                        ui,
+                        row_height,
+                        entry_cells(mutex, entry),
+                        entry_color(entry),
+                    );
+                }
+            });
+    });
+}
+
+/// spawns the node selector and the session field.
+fn spawn_filter_bar(ui: &mut Ui, sorted_node_ids: &[NodeId], state: &mut TimelineState) {
+    ui.horizontal(|ui| {
+        ui.label("Node:");
+        ComboBox::from_id_salt("timeline_node")
+            .selected_text(state.node.map_or("All".to_string(), |id| format!("#{id}")))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut state.node, None, "All");
+                for &id in sorted_node_ids {
+                    ui.selectable_value(&mut state.node, Some(id), format!("#{id}"));
+                }
+            });
+        ui.add_space(10.0);
+        ui.label("Session:");
+        ui.add(
+            TextEdit::singleline(&mut state.session)
+                .desired_width(120.0)
+                .font(TextStyle::Monospace),
+        );
+        if !state.session.is_empty() && ui.small_button("x").clicked() {
+            state.session.clear();
+        }
+        if parse_session(&state.session).is_none() && !state.session.trim().is_empty() {
+            ui.colored_label(Color32::LIGHT_RED, "not a session, use origin/id");
+        }
+    });
+}
+
+/// returns the text of each column for an entry. Only the first line of
+/// the entry's text is shown.
+fn entry_cells(mutex: &MutexGuard<SimulationData>, entry: &LogEntry) -> [String; 6] {
+    let node_type = mutex
+        .node_type(entry.node)
+        .map_or(String::new(), |t| format!("{t:?}"));
+    let session = entry
+        .session()
+        .map_or(String::new(), |(origin, id)| format!("{origin}/{id}"));
+    let hops = entry
+        .hops
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" > ");
+    [
+        format!("{:.3}s", entry.time.as_secs_f64()),
+        format!("#{}", entry.node),
+        node_type,
+        entry.text.lines().next().unwrap_or_default().to_string(),
+        session,
+        hops,
+    ]
+}
+
+/// spawns a row of the table, truncating the cells that don't fit.
+fn spawn_row(ui: &mut Ui, row_height: f32, cells: [String; 6], color: Color32) {
+    ui.horizontal(|ui| {
+        for (i, cell) in cells.into_iter().enumerate() {
+            let text = RichText::new(cell).monospace().color(color);
+            match COLUMN_WIDTHS.get(i) {
+                Some(&width) => {
+                    ui.allocate_ui_with_layout(
+                        vec2(width, row_height),
+                        Layout::left_to_right(Align::Center),
+                        |ui| {
+                            ui.set_min_width(width);
+                            ui.add(Label::new(text).truncate());
+                        },
+                    );
+                }
+                None => {
+                    ui.add(Label::new(text).truncate());
+                }
+            }
+        }
+    });
+}
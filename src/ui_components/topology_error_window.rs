@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use eframe::egui::{vec2, Color32, Context, RichText, ScrollArea, Window};
+
+use crate::topology::TopologyError;
+
+/// Spawns the window explaining why a topology file was rejected.
+/// Closing the window discards the error.
+pub fn spawn(ctx: &Context, error: &mut Option<(PathBuf, TopologyError)>) {
+    let Some((path, e)) = error else {
+        return;
+    };
+    let mut open = true;
+    Window::new("Invalid topology")
+        .open(&mut open)
+        .default_size(vec2(450.0, 250.0))
+        .show(ctx, |ui| {
+            ui.monospace(format!("File: {}", path.display()));
+            ui.add_space(5.0);
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(e.to_string())
+                            .monospace()
+                            .color(Color32::LIGHT_RED),
+                    );
+                });
+        });
+    if !open {
+        *error = None;
+    }
+}
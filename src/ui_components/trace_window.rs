@@ -0,0 +1,123 @@
+use std::sync::MutexGuard;
+
+use eframe::egui::{vec2, Color32, Context, Grid, Id, RichText, ScrollArea, Ui, Window};
+
+use wg_2024::network::NodeId;
+
+use crate::shared_data::{Attempt, SessionKey, SessionOutcome, SessionTrace, SimulationData};
+use crate::ui_components::text::spawn_white_heading;
+
+/// Spawns the window with the trace of a session, opened from the log of
+/// the node with given id. The session is cleared when the window is closed.
+pub fn spawn(
+    ctx: &Context,
+    mutex: &MutexGuard<SimulationData>,
+    id: NodeId,
+    session: &mut Option<SessionKey>,
+) {
+    let Some(key @ (origin, session_id)) = *session else {
+        return;
+    };
+    let mut open = true;
+    Window::new(format!("Session {session_id} of #{origin}"))
+        .id(Id::new(("session_trace", id)))
+        .open(&mut open)
+        .default_size(vec2(450.0, 300.0))
+        .show(ctx, |ui| match mutex.trace_session(key) {
+            None => {
+                ui.label("The events of this session are no longer in the timeline.");
+            }
+            Some(trace) => {
+                spawn_summary(ui, &trace);
+                ui.add_space(5.0);
+                spawn_white_heading(ui, "Fragments");
+                ui.add_space(5.0);
+                ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(250.0)
+                    .show(ui, |ui| spawn_fragments(ui, id, &trace));
+            }
+        });
+    if !open {
+        *session = None;
+    }
+}
+
+/// spawns the origin, destination, latency and outcome of the session.
+fn spawn_summary(ui: &mut Ui, trace: &SessionTrace) {
+    let destination = trace
+        .destination
+        .map_or("unknown".to_string(), |id| format!("#{id}"));
+    ui.monospace(format!("From: #{}   To: {destination}", trace.origin));
+    if let Some(message) = &trace.message {
+        ui.monospace(message);
+    }
+    ui.monospace(format!(
+        "Fragments: {}   Retransmissions: {}",
+        trace.fragments.len(),
+        trace.retransmissions()
+    ));
+    let (outcome, color) = match trace.outcome {
+        SessionOutcome::Delivered(latency) => (
+            format!("Delivered in {:.3}s", latency.as_secs_f64()),
+            Color32::LIGHT_GREEN,
+        ),
+        SessionOutcome::NotDelivered => (
+            format!(
+                "Not delivered, last event at {:.3}s",
+                trace.end.as_secs_f64()
+            ),
+            Color32::LIGHT_RED,
+        ),
+    };
+    ui.label(RichText::new(outcome).monospace().color(color));
+}
+
+/// spawns a row for every transmission of every fragment.
+fn spawn_fragments(ui: &mut Ui, id: NodeId, trace: &SessionTrace) {
+    Grid::new(("session_fragments", id))
+        .striped(true)
+        .show(ui, |ui| {
+            ui.monospace("Fragment");
+            ui.monospace("Ack");
+            ui.monospace("Path");
+            ui.end_row();
+            for (index, fragment) in &trace.fragments {
+                for (i, attempt) in fragment.attempts.iter().enumerate() {
+                    if i == 0 {
+                        ui.monospace(index.to_string());
+                        ui.monospace(if fragment.acked { "yes" } else { "no" });
+                    } else {
+                        ui.monospace("");
+                        ui.monospace("");
+                    }
+                    let (path, color) = attempt_path(attempt);
+                    ui.label(RichText::new(path).monospace().color(color));
+                    ui.end_row();
+                }
+                for nack in &fragment.nacks {
+                    ui.monospace("");
+                    ui.monospace("");
+                    ui.label(RichText::new(nack).monospace().color(Color32::LIGHT_RED));
+                    ui.end_row();
+                }
+            }
+        });
+}
+
+/// returns the nodes reached by a transmission and how it ended.
+fn attempt_path(attempt: &Attempt) -> (String, Color32) {
+    let path = attempt
+        .path()
+        .iter()
+        .map(|id| format!("#{id}"))
+        .collect::<Vec<_>>()
+        .join(" > ");
+    if let Some(id) = attempt.dropped_at {
+        (format!("{path} (dropped by #{id})"), Color32::LIGHT_RED)
+    } else if attempt.delivered {
+        (format!("{path} (delivered)"), Color32::LIGHT_GREEN)
+    } else {
+        (format!("{path} (in flight)"), Color32::GRAY)
+    }
+}